@@ -0,0 +1,215 @@
+use crate::parser::{BinaryOpKind, Expr};
+use crate::polynomial::{rational_approximation, rational_function_from_expr, snap, Polynomial};
+
+/// Ein einzelner Partialbruch `numerator / factor^power`.
+/// `factor` ist normiert und entweder linear oder ein irreduzibles quadratisches Polynom.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialFraction {
+    pub numerator: Polynomial,
+    pub factor: Polynomial,
+    pub power: usize,
+}
+
+/// Partialbruchzerlegung einer gebrochenrationalen Funktion: ganzrationaler Anteil plus Partialbrüche.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decomposition {
+    pub polynomial_part: Polynomial,
+    pub fractions: Vec<PartialFraction>,
+}
+
+/// Zerlegt `numerator / denominator` in Partialbrüche.
+/// Mehrfache lineare Faktoren und irreduzible quadratische Faktoren werden unterstützt.
+pub fn decompose(numerator: &Polynomial, denominator: &Polynomial) -> Result<Decomposition, String> {
+    // Zuerst wird der ganzrationale Anteil per Polynomdivision abgespalten
+    let (polynomial_part, remainder) = numerator.div_rem(denominator)?;
+    let n = denominator.degree();
+    if remainder.is_zero() || n == 0 {
+        return Ok(Decomposition { polynomial_part, fractions: vec![] });
+    }
+
+    let factors = denominator.real_factors();
+    let found_degree: usize = factors.iter().map(|(factor, multiplicity)| factor.degree() * multiplicity).sum();
+    if found_degree != n {
+        return Err("Error: Could not factor denominator".to_string());
+    }
+
+    // Ansatz mit unbestimmten Koeffizienten: Jede Unbekannte gehört zu einem Zählerkoeffizienten
+    // eines Partialbruchs und trägt `x^k * denominator / factor^power` zum Zähler bei.
+    let mut unknowns: Vec<(usize, usize, usize)> = vec![];
+    let mut columns: Vec<Polynomial> = vec![];
+    for (index, (factor, multiplicity)) in factors.iter().enumerate() {
+        for power in 1..=*multiplicity {
+            let mut cofactor = Polynomial::constant(denominator.leading_coefficient());
+            for (other_index, (other, other_multiplicity)) in factors.iter().enumerate() {
+                let exponent = if other_index == index { other_multiplicity - power } else { *other_multiplicity };
+                cofactor = cofactor.mul(&other.pow(exponent));
+            }
+            for k in 0..factor.degree() {
+                unknowns.push((index, power, k));
+                columns.push(cofactor.mul(&Polynomial::monomial(1.0, k)));
+            }
+        }
+    }
+
+    let matrix = (0..n).map(|row| columns.iter().map(|column| column.coefficient(row)).collect()).collect();
+    let rhs = (0..n).map(|row| remainder.coefficient(row)).collect();
+    let solution = solve_linear_system(matrix, rhs)?;
+
+    let mut fractions = vec![];
+    for (index, (factor, multiplicity)) in factors.iter().enumerate() {
+        for power in 1..=*multiplicity {
+            let coeffs = unknowns
+                .iter()
+                .zip(solution.iter())
+                .filter(|((i, p, _), _)| *i == index && *p == power)
+                .map(|(_, value)| snap(*value))
+                .collect();
+            let numerator = Polynomial::new(coeffs);
+            if !numerator.is_zero() {
+                fractions.push(PartialFraction { numerator, factor: factor.clone(), power });
+            }
+        }
+    }
+    Ok(Decomposition { polynomial_part, fractions })
+}
+
+/// Führt die Partialbruchzerlegung eines Ausdrucks in der Variablen `var` durch,
+/// z.B. `1/(x^2-1) = 1/(2(x-1)) - 1/(2(x+1))`.
+pub fn apart(expr: Expr, var: String) -> Result<Expr, String> {
+    let (numerator, denominator) = rational_function_from_expr(expr, var.clone())?;
+    let decomposition = decompose(&numerator, &denominator)?;
+
+    let mut result = if decomposition.polynomial_part.is_zero() {
+        None
+    } else {
+        Some(decomposition.polynomial_part.to_expr(var.clone()))
+    };
+    for fraction in decomposition.fractions {
+        let (negative, term) = partial_fraction_to_expr(&fraction, var.clone());
+        result = Some(match result {
+            None if negative => Expr::UnaryOp(crate::parser::UnaryOpKind::Neg, Box::new(term)),
+            None => term,
+            Some(acc) if negative => Expr::BinaryOp(BinaryOpKind::Sub, Box::new(acc), Box::new(term)),
+            Some(acc) => Expr::BinaryOp(BinaryOpKind::Add, Box::new(acc), Box::new(term)),
+        });
+    }
+    Ok(result.unwrap_or(Expr::Number(0.0)))
+}
+
+// Wandelt einen Partialbruch in einen Ausdruck um. Das Vorzeichen wird separat zurückgegeben,
+// damit es als Subtraktion dargestellt werden kann.
+fn partial_fraction_to_expr(fraction: &PartialFraction, var: String) -> (bool, Expr) {
+    let mut denominator = fraction.factor.to_expr(var.clone());
+    if fraction.power > 1 {
+        denominator = Expr::BinaryOp(
+            BinaryOpKind::Pow,
+            Box::new(denominator),
+            Box::new(Expr::Number(fraction.power as f64)),
+        );
+    }
+
+    if fraction.numerator.degree() == 0 {
+        // Konstante Zähler werden als gekürzter Bruch p / (q * Faktor) dargestellt
+        let value = fraction.numerator.leading_coefficient();
+        let negative = value < 0.0;
+        let (p, q) = rational_approximation(value.abs(), 1000).unwrap_or((0, 1));
+        let (numerator, denominator) = if p == 0 {
+            (Expr::Number(value.abs()), denominator)
+        } else if q == 1 {
+            (Expr::Number(p as f64), denominator)
+        } else {
+            (
+                Expr::Number(p as f64),
+                Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(q as f64)), Box::new(denominator)),
+            )
+        };
+        return (negative, Expr::BinaryOp(BinaryOpKind::Div, Box::new(numerator), Box::new(denominator)));
+    }
+
+    let numerator = fraction.numerator.to_expr(var);
+    (false, Expr::BinaryOp(BinaryOpKind::Div, Box::new(numerator), Box::new(denominator)))
+}
+
+// Löst ein lineares Gleichungssystem mit dem Gauß-Verfahren mit Spaltenpivotsuche
+fn solve_linear_system(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Result<Vec<f64>, String> {
+    let n = rhs.len();
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|a, b| matrix[*a][column].abs().total_cmp(&matrix[*b][column].abs()))
+            .unwrap();
+        if matrix[pivot][column].abs() < 1e-12 {
+            return Err("Error: Singular system in partial fraction decomposition".to_string());
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+        for row in column + 1..n {
+            let factor = matrix[row][column] / matrix[column][column];
+            let pivot_row = matrix[column].clone();
+            for (entry, pivot_entry) in matrix[row].iter_mut().zip(pivot_row.iter()).skip(column) {
+                *entry -= factor * pivot_entry;
+            }
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+    let mut solution = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::render_latex;
+
+    #[test]
+    fn test_apart_difference_of_squares() {
+        let numerator = Polynomial::constant(1.0);
+        let denominator = Polynomial::new(vec![-1.0, 0.0, 1.0]);
+        let decomposition = decompose(&numerator, &denominator).unwrap();
+        assert!(decomposition.polynomial_part.is_zero());
+        assert!(decomposition.fractions.contains(&PartialFraction {
+            numerator: Polynomial::constant(0.5),
+            factor: Polynomial::new(vec![-1.0, 1.0]),
+            power: 1,
+        }));
+        assert!(decomposition.fractions.contains(&PartialFraction {
+            numerator: Polynomial::constant(-0.5),
+            factor: Polynomial::new(vec![1.0, 1.0]),
+            power: 1,
+        }));
+    }
+
+    #[test]
+    fn test_apart_repeated_and_quadratic() {
+        // (x^3 + 1) / ((x - 1)^2 (x^2 + 1)) = 1/(x-1)^2 + 1/(2(x-1)) + (x+1)/(2(x^2+1))
+        let numerator = Polynomial::new(vec![1.0, 0.0, 0.0, 1.0]);
+        let denominator = Polynomial::new(vec![-1.0, 1.0]).pow(2).mul(&Polynomial::new(vec![1.0, 0.0, 1.0]));
+        let decomposition = decompose(&numerator, &denominator).unwrap();
+        assert!(decomposition.polynomial_part.is_zero());
+        assert_eq!(decomposition.fractions.len(), 3);
+        assert!(decomposition.fractions.contains(&PartialFraction {
+            numerator: Polynomial::constant(1.0),
+            factor: Polynomial::new(vec![-1.0, 1.0]),
+            power: 2,
+        }));
+        assert!(decomposition.fractions.contains(&PartialFraction {
+            numerator: Polynomial::new(vec![0.5, 0.5]),
+            factor: Polynomial::new(vec![1.0, 0.0, 1.0]),
+            power: 1,
+        }));
+    }
+
+    #[test]
+    fn test_apart_render() {
+        let expr = Expr::BinaryOp(
+            BinaryOpKind::Div,
+            Box::new(Expr::Number(1.0)),
+            Box::new(Polynomial::new(vec![-1.0, 0.0, 1.0]).to_expr("x".to_string())),
+        );
+        let result = apart(expr, "x".to_string()).unwrap();
+        assert_eq!(render_latex(&result), "\\frac{1}{2 \\cdot (x-1)}-\\frac{1}{2 \\cdot (x+1)}");
+    }
+}
@@ -0,0 +1,380 @@
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Eigenschaften, die für eine Variable angenommen werden können.
+/// Alle Variablen werden grundsätzlich als reell betrachtet.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Property {
+    Positive,
+    Negative,
+    Nonnegative,
+    Nonpositive,
+    Nonzero,
+    Real,
+    Integer,
+    Even,
+    Odd,
+}
+
+// Rechte Seite der Relation, z.B. "> 0" für `x > 0` oder "∈ ℤ" für `n ∈ ℤ`
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Property::Positive => write!(f, "> 0"),
+            Property::Negative => write!(f, "< 0"),
+            Property::Nonnegative => write!(f, "≥ 0"),
+            Property::Nonpositive => write!(f, "≤ 0"),
+            Property::Nonzero => write!(f, "≠ 0"),
+            Property::Real => write!(f, "∈ ℝ"),
+            Property::Integer => write!(f, "∈ ℤ"),
+            Property::Even => write!(f, "∈ 2ℤ"),
+            Property::Odd => write!(f, "∈ 2ℤ + 1"),
+        }
+    }
+}
+
+/// Das Vorzeichen eines Ausdrucks, soweit es sich aus den Annahmen ableiten lässt.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Sign {
+    Positive,
+    Negative,
+    Zero,
+    Nonnegative,
+    Nonpositive,
+    Nonzero,
+    Unknown,
+}
+
+impl Sign {
+    pub fn is_positive(self) -> bool {
+        self == Sign::Positive
+    }
+
+    pub fn is_negative(self) -> bool {
+        self == Sign::Negative
+    }
+
+    pub fn is_nonnegative(self) -> bool {
+        matches!(self, Sign::Positive | Sign::Zero | Sign::Nonnegative)
+    }
+
+    pub fn is_nonpositive(self) -> bool {
+        matches!(self, Sign::Negative | Sign::Zero | Sign::Nonpositive)
+    }
+
+    pub fn is_nonzero(self) -> bool {
+        matches!(self, Sign::Positive | Sign::Negative | Sign::Nonzero)
+    }
+
+    fn from_number(x: f64) -> Sign {
+        if x > 0.0 {
+            Sign::Positive
+        } else if x < 0.0 {
+            Sign::Negative
+        } else if x == 0.0 {
+            Sign::Zero
+        } else {
+            Sign::Unknown
+        }
+    }
+
+    fn negate(self) -> Sign {
+        match self {
+            Sign::Positive => Sign::Negative,
+            Sign::Negative => Sign::Positive,
+            Sign::Nonnegative => Sign::Nonpositive,
+            Sign::Nonpositive => Sign::Nonnegative,
+            other => other,
+        }
+    }
+
+    // Vorzeichen einer Summe
+    fn add(self, other: Sign) -> Sign {
+        match (self, other) {
+            (Sign::Zero, other) | (other, Sign::Zero) => other,
+            (a, b) if a.is_positive() && b.is_nonnegative() || a.is_nonnegative() && b.is_positive() => Sign::Positive,
+            (a, b) if a.is_negative() && b.is_nonpositive() || a.is_nonpositive() && b.is_negative() => Sign::Negative,
+            (a, b) if a.is_nonnegative() && b.is_nonnegative() => Sign::Nonnegative,
+            (a, b) if a.is_nonpositive() && b.is_nonpositive() => Sign::Nonpositive,
+            _ => Sign::Unknown,
+        }
+    }
+
+    // Vorzeichen eines Produkts
+    fn mul(self, other: Sign) -> Sign {
+        match (self, other) {
+            (Sign::Zero, _) | (_, Sign::Zero) => Sign::Zero,
+            (Sign::Positive, other) | (other, Sign::Positive) => other,
+            (Sign::Negative, other) | (other, Sign::Negative) => other.negate(),
+            (Sign::Nonzero, Sign::Nonzero) => Sign::Nonzero,
+            (a, b) if a.is_nonnegative() && b.is_nonnegative() || a.is_nonpositive() && b.is_nonpositive() => {
+                Sign::Nonnegative
+            }
+            (a, b) if a.is_nonnegative() && b.is_nonpositive() || a.is_nonpositive() && b.is_nonnegative() => {
+                Sign::Nonpositive
+            }
+            _ => Sign::Unknown,
+        }
+    }
+}
+
+/// Der Annahmenkontext: die angenommenen Eigenschaften je Variable.
+#[derive(Debug, Default, Clone)]
+pub struct Assumptions {
+    facts: HashMap<String, Vec<Property>>,
+}
+
+thread_local! {
+    // Globaler Kontext wie bei `assume` in anderen CAS; Wasm läuft ohnehin in einem einzigen Thread
+    static ASSUMPTIONS: RefCell<Assumptions> = RefCell::new(Assumptions::default());
+}
+
+/// Nimmt eine Eigenschaft für eine Variable an.
+pub fn assume(var: String, property: Property) {
+    ASSUMPTIONS.with(|assumptions| {
+        let mut assumptions = assumptions.borrow_mut();
+        let facts = assumptions.facts.entry(var).or_default();
+        if !facts.contains(&property) {
+            facts.push(property);
+        }
+    });
+}
+
+/// Entfernt alle Annahmen über eine Variable.
+pub fn forget(var: &str) {
+    ASSUMPTIONS.with(|assumptions| {
+        assumptions.borrow_mut().facts.remove(var);
+    });
+}
+
+/// Entfernt alle Annahmen.
+pub fn forget_all() {
+    ASSUMPTIONS.with(|assumptions| assumptions.borrow_mut().facts.clear());
+}
+
+/// Gibt die angenommenen Eigenschaften einer Variablen zurück.
+pub fn properties(var: &str) -> Vec<Property> {
+    ASSUMPTIONS.with(|assumptions| assumptions.borrow().facts.get(var).cloned().unwrap_or_default())
+}
+
+/// Liest eine Annahme wie `x > 0`, `x <= 0`, `x != 0`, `n ∈ ℤ` oder `n in Z` und nimmt sie an.
+pub fn assume_relation(relation: &str) -> Result<(String, Property), String> {
+    let relation = relation.trim();
+    for (operator, set) in [("∈", true), (" in ", true), (">=", false), ("<=", false), ("!=", false), (">", false), ("<", false)] {
+        let Some((left, right)) = relation.split_once(operator) else {
+            continue;
+        };
+        let var = left.trim();
+        if var.is_empty() || !var.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Error: Expected variable on the left side of '{}'", relation));
+        }
+        let right = right.trim();
+        let property = if set {
+            match right {
+                "ℤ" | "Z" | "integer" => Property::Integer,
+                "ℝ" | "R" | "real" => Property::Real,
+                "2ℤ" | "2Z" | "even" => Property::Even,
+                "odd" => Property::Odd,
+                _ => return Err(format!("Error: Unknown set in assumption: {}", right)),
+            }
+        } else {
+            let bound: f64 = right
+                .parse()
+                .map_err(|_| format!("Error: Expected number on the right side of '{}'", relation))?;
+            match operator {
+                ">" if bound >= 0.0 => Property::Positive,
+                ">=" if bound > 0.0 => Property::Positive,
+                ">=" if bound == 0.0 => Property::Nonnegative,
+                "<" if bound <= 0.0 => Property::Negative,
+                "<=" if bound < 0.0 => Property::Negative,
+                "<=" if bound == 0.0 => Property::Nonpositive,
+                "!=" if bound == 0.0 => Property::Nonzero,
+                _ => return Err(format!("Error: Unsupported assumption: {}", relation)),
+            }
+        };
+        assume(var.to_string(), property);
+        return Ok((var.to_string(), property));
+    }
+    Err(format!("Error: Could not parse assumption: {}", relation))
+}
+
+/// Bestimmt das Vorzeichen eines Ausdrucks aus den Annahmen über seine Variablen.
+pub fn sign(expr: &Expr) -> Sign {
+    match expr {
+        Expr::Number(x) => Sign::from_number(*x),
+        Expr::Var(v) if v == "pi" || v == "e" => Sign::Positive,
+        Expr::Var(v) => {
+            let facts = properties(v);
+            if facts.contains(&Property::Positive) {
+                Sign::Positive
+            } else if facts.contains(&Property::Negative) {
+                Sign::Negative
+            } else if facts.contains(&Property::Nonnegative) && facts.contains(&Property::Nonzero) {
+                Sign::Positive
+            } else if facts.contains(&Property::Nonpositive) && facts.contains(&Property::Nonzero) {
+                Sign::Negative
+            } else if facts.contains(&Property::Nonnegative) {
+                Sign::Nonnegative
+            } else if facts.contains(&Property::Nonpositive) {
+                Sign::Nonpositive
+            } else if facts.contains(&Property::Nonzero) || facts.contains(&Property::Odd) {
+                Sign::Nonzero
+            } else {
+                Sign::Unknown
+            }
+        }
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => sign(inner).negate(),
+        Expr::BinaryOp(op, left, right) => {
+            let (a, b) = (sign(left), sign(right));
+            match op {
+                BinaryOpKind::Add => a.add(b),
+                BinaryOpKind::Sub => a.add(b.negate()),
+                BinaryOpKind::Mul => a.mul(b),
+                BinaryOpKind::Div if b.is_nonzero() => a.mul(b),
+                BinaryOpKind::Div => Sign::Unknown,
+                BinaryOpKind::Pow => power_sign(right, a),
+            }
+        }
+        Expr::Call(func, args) => match (&**func, args.as_slice()) {
+            (Expr::Var(name), [_]) if name == "exp" || name == "cosh" => Sign::Positive,
+            (Expr::Var(name), [_]) if name == "acos" => Sign::Nonnegative,
+            (Expr::Var(name), [arg]) if name == "sqrt" && sign(arg).is_positive() => Sign::Positive,
+            (Expr::Var(name), [arg]) if name == "abs" && sign(arg).is_nonzero() => Sign::Positive,
+            (Expr::Var(name), [_]) if name == "sqrt" || name == "abs" => Sign::Nonnegative,
+            // Ungerade, monotone Funktionen übernehmen das Vorzeichen ihres Arguments
+            (Expr::Var(name), [arg]) if matches!(name.as_str(), "id" | "atan" | "asin" | "sinh" | "tanh" | "asinh" | "atanh" | "erf" | "sign") => sign(arg),
+            _ => Sign::Unknown,
+        },
+        Expr::Derivative(_, _) | Expr::Matrix(_) | Expr::Equation(_, _) => Sign::Unknown,
+    }
+}
+
+// Vorzeichen einer Potenz: gerade Exponenten sind nichtnegativ, positive Basen bleiben positiv
+fn power_sign(exponent: &Expr, base_sign: Sign) -> Sign {
+    if base_sign.is_positive() {
+        return Sign::Positive;
+    }
+    if is_even(exponent) {
+        return if base_sign.is_nonzero() { Sign::Positive } else { Sign::Nonnegative };
+    }
+    if is_odd(exponent) {
+        return base_sign;
+    }
+    if base_sign == Sign::Zero && sign(exponent).is_positive() {
+        return Sign::Zero;
+    }
+    Sign::Unknown
+}
+
+/// Prüft, ob ein Ausdruck unter den Annahmen sicher ganzzahlig ist.
+pub fn is_integer(expr: &Expr) -> bool {
+    match expr {
+        Expr::Number(x) => x.fract() == 0.0,
+        Expr::Var(v) => properties(v).iter().any(|p| matches!(p, Property::Integer | Property::Even | Property::Odd)),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => is_integer(inner),
+        Expr::BinaryOp(BinaryOpKind::Add | BinaryOpKind::Sub | BinaryOpKind::Mul, left, right) => {
+            is_integer(left) && is_integer(right)
+        }
+        Expr::BinaryOp(BinaryOpKind::Pow, base, exponent) => {
+            is_integer(base) && is_integer(exponent) && sign(exponent).is_nonnegative()
+        }
+        _ => false,
+    }
+}
+
+/// Prüft, ob ein Ausdruck unter den Annahmen sicher eine gerade Zahl ist.
+pub fn is_even(expr: &Expr) -> bool {
+    match expr {
+        Expr::Number(x) => x.fract() == 0.0 && x % 2.0 == 0.0,
+        Expr::Var(v) => properties(v).contains(&Property::Even),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => is_even(inner),
+        Expr::BinaryOp(BinaryOpKind::Add | BinaryOpKind::Sub, left, right) => {
+            (is_even(left) && is_even(right)) || (is_odd(left) && is_odd(right))
+        }
+        Expr::BinaryOp(BinaryOpKind::Mul, left, right) => {
+            (is_even(left) && is_integer(right)) || (is_integer(left) && is_even(right))
+        }
+        Expr::BinaryOp(BinaryOpKind::Pow, base, exponent) => {
+            is_even(base) && is_integer(exponent) && sign(exponent).is_positive()
+        }
+        _ => false,
+    }
+}
+
+/// Prüft, ob ein Ausdruck unter den Annahmen sicher eine ungerade Zahl ist.
+pub fn is_odd(expr: &Expr) -> bool {
+    match expr {
+        Expr::Number(x) => x.fract() == 0.0 && x % 2.0 != 0.0,
+        Expr::Var(v) => properties(v).contains(&Property::Odd),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => is_odd(inner),
+        Expr::BinaryOp(BinaryOpKind::Add | BinaryOpKind::Sub, left, right) => {
+            (is_even(left) && is_odd(right)) || (is_odd(left) && is_even(right))
+        }
+        Expr::BinaryOp(BinaryOpKind::Mul, left, right) => is_odd(left) && is_odd(right),
+        Expr::BinaryOp(BinaryOpKind::Pow, base, exponent) => {
+            is_odd(base) && is_integer(exponent) && sign(exponent).is_nonnegative()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str) -> Expr {
+        Expr::Var(name.to_string())
+    }
+
+    #[test]
+    fn test_sign_inference() {
+        forget_all();
+        assume_relation("x > 0").unwrap();
+        assume_relation("y < 0").unwrap();
+        // x^2 * (x + 1) > 0, x * y < 0, z^2 >= 0
+        let expr = Expr::BinaryOp(
+            BinaryOpKind::Mul,
+            Box::new(Expr::BinaryOp(BinaryOpKind::Pow, Box::new(var("x")), Box::new(Expr::Number(2.0)))),
+            Box::new(Expr::BinaryOp(BinaryOpKind::Add, Box::new(var("x")), Box::new(Expr::Number(1.0)))),
+        );
+        assert_eq!(sign(&expr), Sign::Positive);
+        assert_eq!(sign(&Expr::BinaryOp(BinaryOpKind::Mul, Box::new(var("x")), Box::new(var("y")))), Sign::Negative);
+        assert_eq!(
+            sign(&Expr::BinaryOp(BinaryOpKind::Pow, Box::new(var("z")), Box::new(Expr::Number(2.0)))),
+            Sign::Nonnegative
+        );
+        forget("x");
+        assert_eq!(sign(&var("x")), Sign::Unknown);
+    }
+
+    #[test]
+    fn test_sqrt_of_square() {
+        use crate::simplify::simplify;
+        forget_all();
+        let sqrt_square = |name: &str| {
+            let square = Expr::BinaryOp(BinaryOpKind::Pow, Box::new(var(name)), Box::new(Expr::Number(2.0)));
+            Expr::BinaryOp(BinaryOpKind::Pow, Box::new(square), Box::new(Expr::Number(0.5)))
+        };
+        // Ohne Annahme ist sqrt(x^2) = |x|
+        let abs = Expr::Call(Box::new(var("abs")), vec![var("x")]);
+        assert_eq!(simplify(sqrt_square("x"), false), abs);
+        assume_relation("x >= 0").unwrap();
+        assume_relation("y < 0").unwrap();
+        assert_eq!(simplify(sqrt_square("x"), false), var("x"));
+        assert_eq!(simplify(sqrt_square("y"), false), Expr::UnaryOp(UnaryOpKind::Neg, Box::new(var("y"))));
+        forget_all();
+    }
+
+    #[test]
+    fn test_parity_inference() {
+        forget_all();
+        assume_relation("n ∈ ℤ").unwrap();
+        assert_eq!(assume_relation("m in odd").map(|(var, property)| format!("{} {}", var, property)), Ok("m ∈ 2ℤ + 1".to_string()));
+        let two_n = Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(2.0)), Box::new(var("n")));
+        assert!(is_even(&two_n));
+        assert!(is_odd(&Expr::BinaryOp(BinaryOpKind::Add, Box::new(two_n), Box::new(var("m")))));
+        assert!(!is_integer(&var("k")));
+    }
+}
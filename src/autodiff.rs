@@ -0,0 +1,286 @@
+use crate::differentiate::{elementary_derivative, is_elementary_function};
+use crate::evaluate::{binary, elementary, evaluate, log, Environment, EvalError};
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+
+/// Wert und Gradient eines Ausdrucks an einem Punkt.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Gradient {
+    pub value: f64,
+    pub gradient: Vec<f64>,
+}
+
+/// Vorwärtsmodus mit dualen Zahlen: Jeder Teilausdruck trägt seinen Wert und seine Ableitungen
+/// nach allen Variablen in `vars` mit. Andere Variablen werden aus `env` gelesen.
+pub fn forward_gradient(expr: &Expr, vars: &[String], point: &[f64], env: &Environment) -> Result<Gradient, EvalError> {
+    check_point(vars, point)?;
+    let dual = forward(expr, vars, point, env)?;
+    Ok(Gradient { value: dual.value, gradient: dual.tangent })
+}
+
+/// Rückwärtsmodus: Der Ausdruck wird einmal vorwärts ausgewertet und dabei auf einem Band
+/// aufgezeichnet, danach werden die Adjungierten in einem Rückwärtsdurchlauf aufsummiert.
+pub fn reverse_gradient(expr: &Expr, vars: &[String], point: &[f64], env: &Environment) -> Result<Gradient, EvalError> {
+    check_point(vars, point)?;
+    let mut tape = Tape { nodes: vec![], vars, point, env };
+    let result = tape.record(expr)?;
+    let mut adjoints = vec![0.0; tape.nodes.len()];
+    adjoints[result] = 1.0;
+    let mut gradient = vec![0.0; vars.len()];
+    for index in (0..tape.nodes.len()).rev() {
+        let adjoint = adjoints[index];
+        match &tape.nodes[index].kind {
+            NodeKind::Variable(i) => gradient[*i] += adjoint,
+            NodeKind::Operation(parents) => {
+                for (parent, partial) in parents {
+                    adjoints[*parent] += chain(*partial, adjoint);
+                }
+            }
+            NodeKind::Constant => {}
+        }
+    }
+    Ok(Gradient { value: tape.nodes[result].value, gradient })
+}
+
+fn check_point(vars: &[String], point: &[f64]) -> Result<(), EvalError> {
+    if vars.len() != point.len() {
+        return Err(EvalError::WrongArgumentCount { function: "gradient".to_string(), expected: vars.len(), found: point.len() });
+    }
+    Ok(())
+}
+
+// Beitrag einer Kante zur Kettenregel: partielle Ableitung mal Ableitung (Tangente im Vorwärts-,
+// Adjungierte im Rückwärtsmodus). Ist einer der Faktoren 0, ist der Beitrag 0, auch wenn der andere
+// undefiniert ist; so ergeben beide Modi z.B. für sqrt(x^2) bei x = 0 dasselbe.
+fn chain(partial: f64, derivative: f64) -> f64 {
+    if partial == 0.0 || derivative == 0.0 {
+        0.0
+    } else {
+        partial * derivative
+    }
+}
+
+// Wert und partielle Ableitungen einer Operation nach ihren Operanden. Beide Modi verwenden diese
+// Regeln, für Funktionen wird die Ableitungstabelle aus `differentiate` ausgewertet. Undefinierte
+// partielle Ableitungen wie die von sqrt bei 0 sind NaN und wirken sich nur aus, wenn sie mit einer
+// Ableitung ungleich 0 multipliziert werden.
+fn local_partials(op: &Operation, args: &[f64], env: &Environment) -> Result<(f64, Vec<f64>), EvalError> {
+    match (op, args) {
+        (Operation::Binary(op), [a, b]) => {
+            let value = binary(*op, *a, *b)?;
+            let partials = match op {
+                BinaryOpKind::Add => vec![1.0, 1.0],
+                BinaryOpKind::Sub => vec![1.0, -1.0],
+                BinaryOpKind::Mul => vec![*b, *a],
+                BinaryOpKind::Div => vec![1.0 / b, -a / (b * b)],
+                // d/da a^b = b a^(b-1), d/db a^b = a^b ln(a); für a <= 0 ist nur ein konstanter Exponent erlaubt
+                BinaryOpKind::Pow => {
+                    let base_partial = if *b == 0.0 { 0.0 } else { b * binary(BinaryOpKind::Pow, *a, b - 1.0)? };
+                    let exponent_partial = if *a > 0.0 { value * a.ln() } else { f64::NAN };
+                    vec![base_partial, exponent_partial]
+                }
+            };
+            Ok((value, partials))
+        }
+        (Operation::Neg, [a]) => Ok((-a, vec![-1.0])),
+        (Operation::Elementary(name), [x]) => {
+            let value = elementary(name, *x)?;
+            let derivative = Expr::Call(Box::new(elementary_derivative(name)), vec![Expr::Number(*x)]);
+            Ok((value, vec![evaluate(&derivative, env).unwrap_or(f64::NAN)]))
+        }
+        // log(b, x) = ln(x) / ln(b)
+        (Operation::Log, [b, x]) => {
+            let value = log(*b, *x)?;
+            Ok((value, vec![-value / (b * b.ln()), 1.0 / (x * b.ln())]))
+        }
+        _ => unreachable!(),
+    }
+}
+
+// Eine Operation im Ausdrucksbaum, deren Operanden bereits ausgewertet sind
+enum Operation {
+    Binary(BinaryOpKind),
+    Neg,
+    Elementary(String),
+    Log,
+}
+
+// Zerlegt einen Ausdruck in Operation und Operanden. Variablen und Zahlen haben keine Operanden.
+fn operation(expr: &Expr) -> Result<(Operation, Vec<&Expr>), EvalError> {
+    match expr {
+        Expr::BinaryOp(op, left, right) => Ok((Operation::Binary(*op), vec![&**left, &**right])),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => Ok((Operation::Neg, vec![&**inner])),
+        Expr::Call(func, args) => match (&**func, args.as_slice()) {
+            (Expr::Var(name), [b, x]) if name == "log" => Ok((Operation::Log, vec![b, x])),
+            (Expr::Var(name), [arg]) if is_elementary_function(name) => Ok((Operation::Elementary(name.clone()), vec![arg])),
+            (Expr::Var(name), args) if is_elementary_function(name) || name == "log" => Err(EvalError::WrongArgumentCount {
+                function: name.clone(),
+                expected: if name == "log" { 2 } else { 1 },
+                found: args.len(),
+            }),
+            (Expr::Var(name), _) => Err(EvalError::UnknownFunction(name.clone())),
+            // Punktfreie Funktionen werden nur in der Ableitungstabelle verwendet
+            (func, _) => Err(EvalError::UnknownFunction(crate::render::render_latex(func))),
+        },
+        _ => Err(EvalError::NotScalar),
+    }
+}
+
+// Duale Zahl mit einem Tangentenvektor für alle Variablen zugleich
+struct Dual {
+    value: f64,
+    tangent: Vec<f64>,
+}
+
+fn forward(expr: &Expr, vars: &[String], point: &[f64], env: &Environment) -> Result<Dual, EvalError> {
+    let tangent = vec![0.0; vars.len()];
+    match expr {
+        Expr::Number(x) => Ok(Dual { value: *x, tangent }),
+        Expr::Var(name) => match vars.iter().position(|var| var == name) {
+            Some(i) => {
+                let mut tangent = tangent;
+                tangent[i] = 1.0;
+                Ok(Dual { value: point[i], tangent })
+            }
+            None => Ok(Dual { value: evaluate(expr, env)?, tangent }),
+        },
+        _ => {
+            let (op, operands) = operation(expr)?;
+            let operands = operands
+                .into_iter()
+                .map(|operand| forward(operand, vars, point, env))
+                .collect::<Result<Vec<Dual>, EvalError>>()?;
+            let values: Vec<f64> = operands.iter().map(|operand| operand.value).collect();
+            let (value, partials) = local_partials(&op, &values, env)?;
+            let mut tangent = tangent;
+            for (operand, partial) in operands.iter().zip(partials) {
+                for (t, dt) in tangent.iter_mut().zip(&operand.tangent) {
+                    *t += chain(partial, *dt);
+                }
+            }
+            Ok(Dual { value, tangent })
+        }
+    }
+}
+
+enum NodeKind {
+    Constant,
+    Variable(usize),
+    /// Operanden mit den partiellen Ableitungen der Operation nach ihnen
+    Operation(Vec<(usize, f64)>),
+}
+
+struct Node {
+    value: f64,
+    kind: NodeKind,
+}
+
+struct Tape<'a> {
+    nodes: Vec<Node>,
+    vars: &'a [String],
+    point: &'a [f64],
+    env: &'a Environment,
+}
+
+impl Tape<'_> {
+    fn push(&mut self, value: f64, kind: NodeKind) -> usize {
+        self.nodes.push(Node { value, kind });
+        self.nodes.len() - 1
+    }
+
+    fn record(&mut self, expr: &Expr) -> Result<usize, EvalError> {
+        match expr {
+            Expr::Number(x) => Ok(self.push(*x, NodeKind::Constant)),
+            Expr::Var(name) => match self.vars.iter().position(|var| var == name) {
+                Some(i) => Ok(self.push(self.point[i], NodeKind::Variable(i))),
+                None => {
+                    let value = evaluate(expr, self.env)?;
+                    Ok(self.push(value, NodeKind::Constant))
+                }
+            },
+            _ => {
+                let (op, operands) = operation(expr)?;
+                let operands = operands.into_iter().map(|operand| self.record(operand)).collect::<Result<Vec<usize>, EvalError>>()?;
+                let values: Vec<f64> = operands.iter().map(|&operand| self.nodes[operand].value).collect();
+                let (value, partials) = local_partials(&op, &values, self.env)?;
+                // Konstante Operanden haben keine Adjungierte und werden übergangen
+                let parents = operands
+                    .into_iter()
+                    .zip(partials)
+                    .filter(|(operand, _)| !matches!(self.nodes[*operand].kind, NodeKind::Constant))
+                    .collect::<Vec<_>>();
+                let kind = if parents.is_empty() { NodeKind::Constant } else { NodeKind::Operation(parents) };
+                Ok(self.push(value, kind))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differentiate::differentiate;
+    use crate::parser::parse;
+
+    // Vergleicht Vorwärts- und Rückwärtsmodus mit der symbolischen Ableitung
+    fn assert_modes_agree(source: &str, vars: &[&str], point: &[f64]) {
+        let expr = parse(source);
+        let vars: Vec<String> = vars.iter().map(|var| var.to_string()).collect();
+        let mut env = Environment::new();
+        for (var, value) in vars.iter().zip(point) {
+            env.set_variable(var.clone(), *value);
+        }
+        let forward = forward_gradient(&expr, &vars, point, &env).unwrap();
+        let reverse = reverse_gradient(&expr, &vars, point, &env).unwrap();
+        let value = evaluate(&expr, &env).unwrap();
+        assert!((forward.value - value).abs() < 1e-12 && (reverse.value - value).abs() < 1e-12, "{}", source);
+        for (i, var) in vars.iter().enumerate() {
+            let (f, r) = (forward.gradient[i], reverse.gradient[i]);
+            assert!((f - r).abs() <= 1e-12 * (1.0 + f.abs()), "{}: forward {} != reverse {}", source, f, r);
+            // An Stellen, an denen die symbolische Ableitung undefiniert ist, werden nur die Modi verglichen
+            let Ok(symbolic) = evaluate(&differentiate(expr.clone(), var.clone()), &env) else {
+                continue;
+            };
+            let tolerance = 1e-10 * (1.0 + symbolic.abs());
+            assert!((forward.gradient[i] - symbolic).abs() < tolerance, "{}: forward {} != {}", source, forward.gradient[i], symbolic);
+            assert!((reverse.gradient[i] - symbolic).abs() < tolerance, "{}: reverse {} != {}", source, reverse.gradient[i], symbolic);
+        }
+    }
+
+    #[test]
+    fn test_modes_agree() {
+        assert_modes_agree("x^2*y + sin(x*y)", &["x", "y"], &[0.7, -1.3]);
+        assert_modes_agree("exp(-x/2) * ln(y + 3) / (1 + x^2)", &["x", "y"], &[1.5, 0.2]);
+        assert_modes_agree("x^y + atan(x - y) + sqrt(x^2 + y^2)", &["x", "y"], &[1.2, 0.8]);
+        assert_modes_agree("erf(x) * gamma(y) + log(2, x*y) - tanh(x)^3", &["x", "y"], &[0.9, 2.5]);
+        assert_modes_agree("cosh(x) * asinh(y) * z + abs(z - x)", &["x", "y", "z"], &[0.3, -0.4, 2.0]);
+        // Negative Basis mit konstantem Exponenten
+        assert_modes_agree("(x - 3)^3 + (-2)^2 * x", &["x"], &[1.0]);
+        // Undefinierte partielle Ableitungen mal eine verschwindende Ableitung
+        assert_modes_agree("sqrt(x^2)", &["x"], &[0.0]);
+        assert_modes_agree("(-2)^(x^2) + y*sqrt(x^2)", &["x", "y"], &[0.0, 1.5]);
+        // id' = 1 stammt aus derselben Ableitungstabelle
+        assert_modes_agree("id(x^2) * y", &["x", "y"], &[1.5, -2.0]);
+    }
+
+    #[test]
+    fn test_shared_subexpressions() {
+        // Der Rückwärtsmodus summiert die Beiträge mehrfach verwendeter Variablen auf
+        let expr = parse("x*x*x");
+        let vars = ["x".to_string()];
+        let reverse = reverse_gradient(&expr, &vars, &[2.0], &Environment::new()).unwrap();
+        assert_eq!(reverse, Gradient { value: 8.0, gradient: vec![12.0] });
+        assert_eq!(forward_gradient(&expr, &vars, &[2.0], &Environment::new()), Ok(reverse));
+    }
+
+    #[test]
+    fn test_errors() {
+        let vars = ["x".to_string()];
+        let env = Environment::new();
+        assert_eq!(
+            forward_gradient(&parse("ln(x)"), &vars, &[-1.0], &env),
+            Err(EvalError::Domain { function: "ln".to_string(), argument: -1.0 })
+        );
+        assert_eq!(reverse_gradient(&parse("f(x)"), &vars, &[1.0], &env), Err(EvalError::UnknownFunction("f".to_string())));
+        assert!(reverse_gradient(&parse("x"), &vars, &[], &env).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::constants::{E, PI};
+use crate::differentiate::{derivative_name, is_elementary_function, ELEMENTARY_FUNCTIONS};
+use crate::evaluate::{binary, elementary, log, EvalError};
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+
+/// Ein Befehl der Registermaschine. Jeder Befehl schreibt in das Register mit seinem eigenen Index,
+/// Operanden verweisen auf frühere Register.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Instruction {
+    Const(f64),
+    /// Lädt das Argument mit dem gegebenen Index
+    Load(usize),
+    Binary(BinaryOpKind, usize, usize),
+    Neg(usize),
+    /// Elementare Funktion wie `sin` oder `ln`
+    Call(&'static str, usize),
+    /// Logarithmus zur Basis des ersten Registers
+    Log(usize, usize),
+}
+
+/// Ein in Bytecode übersetzter Ausdruck, der für viele Punkte schnell ausgewertet werden kann.
+#[derive(Debug, Clone)]
+pub struct CompiledFn {
+    instructions: Vec<Instruction>,
+    result: usize,
+    arity: usize,
+}
+
+impl CompiledFn {
+    /// Wertet die Funktion für die Argumente in der Reihenfolge der Variablen aus `compile` aus.
+    pub fn call(&self, args: &[f64]) -> Result<f64, EvalError> {
+        if args.len() != self.arity {
+            return Err(EvalError::WrongArgumentCount { function: "compiled function".to_string(), expected: self.arity, found: args.len() });
+        }
+        let mut registers = Vec::with_capacity(self.instructions.len());
+        for instruction in &self.instructions {
+            let value = match *instruction {
+                Instruction::Const(c) => c,
+                Instruction::Load(i) => args[i],
+                Instruction::Binary(op, a, b) => binary(op, registers[a], registers[b])?,
+                Instruction::Neg(a) => -registers[a],
+                Instruction::Call(name, a) => elementary(name, registers[a])?,
+                Instruction::Log(b, a) => log(registers[b], registers[a])?,
+            };
+            registers.push(value);
+        }
+        Ok(registers[self.result])
+    }
+}
+
+/// Übersetzt einen Ausdruck in den Variablen `vars` in Bytecode. Gleiche Teilausdrücke werden nur
+/// einmal berechnet, Teilausdrücke aus Konstanten werden schon beim Übersetzen ausgewertet.
+pub fn compile(expr: &Expr, vars: &[String]) -> Result<CompiledFn, EvalError> {
+    let mut compiler = Compiler { instructions: vec![], vars };
+    let result = compiler.expression(expr)?;
+    Ok(CompiledFn { instructions: compiler.instructions, result, arity: vars.len() })
+}
+
+struct Compiler<'a> {
+    instructions: Vec<Instruction>,
+    vars: &'a [String],
+}
+
+impl Compiler<'_> {
+    // Fügt einen Befehl hinzu oder verwendet ein Register mit demselben Befehl wieder.
+    // Da Operanden Registerindizes sind, erkennt der Vergleich auch gleiche Teilbäume.
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        if let Some(index) = self.instructions.iter().position(|other| *other == instruction) {
+            return index;
+        }
+        self.instructions.push(instruction);
+        self.instructions.len() - 1
+    }
+
+    fn constant(&self, register: usize) -> Option<f64> {
+        match self.instructions[register] {
+            Instruction::Const(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn expression(&mut self, expr: &Expr) -> Result<usize, EvalError> {
+        match expr {
+            Expr::Number(x) => Ok(self.emit(Instruction::Const(*x))),
+            Expr::Var(name) => match self.vars.iter().position(|var| var == name) {
+                Some(index) => Ok(self.emit(Instruction::Load(index))),
+                None if name == "pi" => Ok(self.emit(Instruction::Const(PI))),
+                None if name == "e" => Ok(self.emit(Instruction::Const(E))),
+                None => Err(EvalError::UnknownVariable(name.clone())),
+            },
+            Expr::UnaryOp(UnaryOpKind::Neg, inner) => {
+                let inner = self.expression(inner)?;
+                Ok(self.neg(inner))
+            }
+            Expr::BinaryOp(op, left, right) => {
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                self.binary(*op, left, right)
+            }
+            Expr::Call(func, args) => {
+                let args = args.iter().map(|arg| self.expression(arg)).collect::<Result<Vec<usize>, EvalError>>()?;
+                self.apply(func, &args)
+            }
+            Expr::Derivative(func, indices) => Err(EvalError::UnknownVariable(derivative_name(func, indices))),
+            Expr::Matrix(_) | Expr::Equation(_, _) => Err(EvalError::NotScalar),
+        }
+    }
+
+    fn neg(&mut self, register: usize) -> usize {
+        match self.constant(register) {
+            Some(c) => self.emit(Instruction::Const(-c)),
+            None => self.emit(Instruction::Neg(register)),
+        }
+    }
+
+    fn binary(&mut self, op: BinaryOpKind, left: usize, right: usize) -> Result<usize, EvalError> {
+        match (self.constant(left), self.constant(right)) {
+            (Some(a), Some(b)) => {
+                let value = binary(op, a, b)?;
+                Ok(self.emit(Instruction::Const(value)))
+            }
+            _ => Ok(self.emit(Instruction::Binary(op, left, right))),
+        }
+    }
+
+    // Übersetzt den Aufruf einer (evtl. punktfrei zusammengesetzten) Funktion auf die Argumentregister
+    fn apply(&mut self, func: &Expr, args: &[usize]) -> Result<usize, EvalError> {
+        match func {
+            Expr::Number(c) => Ok(self.emit(Instruction::Const(*c))),
+            Expr::Var(name) if name == "pi" || name == "e" => self.expression(func),
+            Expr::Var(name) if name == "log" => match args {
+                [b, x] => match (self.constant(*b), self.constant(*x)) {
+                    (Some(b), Some(x)) => {
+                        let value = log(b, x)?;
+                        Ok(self.emit(Instruction::Const(value)))
+                    }
+                    _ => Ok(self.emit(Instruction::Log(*b, *x))),
+                },
+                _ => Err(EvalError::WrongArgumentCount { function: name.clone(), expected: 2, found: args.len() }),
+            },
+            Expr::Var(name) => {
+                let [arg] = args else {
+                    return Err(if is_elementary_function(name) {
+                        EvalError::WrongArgumentCount { function: name.clone(), expected: 1, found: args.len() }
+                    } else {
+                        EvalError::UnknownFunction(name.clone())
+                    });
+                };
+                // Der Name wird auf den statischen Eintrag der Funktionsliste abgebildet
+                let Some(&name) = ELEMENTARY_FUNCTIONS.iter().find(|function| **function == name.as_str()) else {
+                    return Err(EvalError::UnknownFunction(name.clone()));
+                };
+                if name == "id" {
+                    return Ok(*arg);
+                }
+                match self.constant(*arg) {
+                    Some(c) => {
+                        let value = elementary(name, c)?;
+                        Ok(self.emit(Instruction::Const(value)))
+                    }
+                    None => Ok(self.emit(Instruction::Call(name, *arg))),
+                }
+            }
+            Expr::UnaryOp(UnaryOpKind::Neg, inner) => {
+                let inner = self.apply(inner, args)?;
+                Ok(self.neg(inner))
+            }
+            Expr::BinaryOp(op, left, right) => {
+                let left = self.apply(left, args)?;
+                let right = self.apply(right, args)?;
+                self.binary(*op, left, right)
+            }
+            Expr::Call(outer, inner) => {
+                // Verkettung: f(g)(x) = f(g(x))
+                let inner = inner.iter().map(|g| self.apply(g, args)).collect::<Result<Vec<usize>, EvalError>>()?;
+                self.apply(outer, &inner)
+            }
+            Expr::Derivative(func, indices) => Err(EvalError::UnknownFunction(derivative_name(func, indices))),
+            Expr::Matrix(_) | Expr::Equation(_, _) => Err(EvalError::NotScalar),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate::{evaluate, Environment};
+    use crate::parser::parse;
+
+    #[test]
+    fn test_compile_matches_evaluate() {
+        let expr = parse("sin(x)^2 + x*exp(-x/2) - ln(x + 1)");
+        let compiled = compile(&expr, &["x".to_string()]).unwrap();
+        let mut env = Environment::new();
+        for i in 0..50 {
+            let x = i as f64 * 0.1;
+            env.set_variable("x".to_string(), x);
+            assert_eq!(compiled.call(&[x]), evaluate(&expr, &env));
+        }
+    }
+
+    #[test]
+    fn test_common_subexpressions() {
+        // sin(x) wird nur einmal berechnet: x, sin(x), 2, sin(x)^2, Summe
+        let compiled = compile(&parse("sin(x)^2 + sin(x)"), &["x".to_string()]).unwrap();
+        assert_eq!(compiled.instructions.len(), 5);
+        // Konstante Teilausdrücke werden beim Übersetzen ausgewertet: x, pi, cos(pi) = -1, Produkt
+        let compiled = compile(&parse("x * cos(pi)"), &["x".to_string()]).unwrap();
+        assert_eq!(compiled.instructions.len(), 4);
+        assert_eq!(compiled.call(&[2.0]), Ok(-2.0));
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let vars = ["x".to_string()];
+        assert_eq!(compile(&parse("x + y"), &vars).err(), Some(EvalError::UnknownVariable("y".to_string())));
+        let compiled = compile(&parse("ln(x)"), &vars).unwrap();
+        assert_eq!(compiled.call(&[0.0]), Err(EvalError::Domain { function: "ln".to_string(), argument: 0.0 }));
+    }
+}
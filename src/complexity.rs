@@ -0,0 +1,211 @@
+use crate::apart::apart;
+use crate::log::combine_log;
+use crate::parser::{BinaryOpKind, Expr};
+use crate::polynomial::{rational_approximation, rational_function_from_expr, Polynomial};
+use crate::render::render_latex;
+use crate::simplify::{build_product, build_sum, collect, expand, simplify};
+use crate::trig::trigsimp;
+
+/// Maß für die Komplexität eines Ausdrucks, nach dem die einfachste Form ausgewählt wird.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Measure {
+    /// Anzahl aller Knoten im Ausdrucksbaum
+    NodeCount,
+    /// Anzahl der Zahlen und Variablen
+    LeafCount,
+    /// Länge der LaTeX-Darstellung
+    LatexLength,
+}
+
+impl Measure {
+    /// Liest ein Maß aus seinem Namen (`nodes`, `leaves` oder `latex`).
+    pub fn from_name(name: &str) -> Result<Measure, String> {
+        match name {
+            "nodes" => Ok(Measure::NodeCount),
+            "leaves" => Ok(Measure::LeafCount),
+            "latex" => Ok(Measure::LatexLength),
+            _ => Err(format!("Error: Unknown complexity measure: {}", name)),
+        }
+    }
+}
+
+/// Berechnet die Komplexität eines Ausdrucks im gegebenen Maß.
+pub fn complexity(expr: &Expr, measure: Measure) -> usize {
+    match measure {
+        Measure::NodeCount => node_count(expr),
+        Measure::LeafCount => leaf_count(expr),
+        Measure::LatexLength => render_latex(expr).chars().count(),
+    }
+}
+
+/// Probiert mehrere Umformungen (Ausmultiplizieren, Zusammenfassen, Faktorisieren, Kürzen,
+/// Partialbrüche, trigonometrische und logarithmische Vereinfachung) und gibt die Form
+/// mit der kleinsten Komplexität zurück. Bei Gleichstand gewinnt die frühere Strategie.
+pub fn simplest_form(expr: Expr, measure: Measure) -> Expr {
+    let mut best = simplify(expr, false);
+    // Das Ergebnis einer Strategie kann durch eine andere weiter verkürzt werden
+    for _ in 0..4 {
+        let cost = complexity(&best, measure);
+        let next = candidates(&best)
+            .into_iter()
+            .min_by_key(|candidate| complexity(candidate, measure))
+            .unwrap_or(best.clone());
+        if complexity(&next, measure) >= cost {
+            break;
+        }
+        best = next;
+    }
+    best
+}
+
+// Alle Umformungen eines Ausdrucks, die als einfachste Form in Frage kommen
+fn candidates(expr: &Expr) -> Vec<Expr> {
+    let mut result = vec![
+        collect(expr.clone()),
+        expand(expr.clone()),
+        trigsimp(expr.clone()),
+        combine_log(expr.clone()),
+    ];
+    let variables = variables(expr);
+    if let [var] = variables.as_slice() {
+        result.extend(rational_forms(expr, var));
+    }
+    result
+}
+
+// Faktorisierte, gekürzte und partialbruchzerlegte Form einer gebrochenrationalen Funktion in `var`
+fn rational_forms(expr: &Expr, var: &str) -> Vec<Expr> {
+    let Ok((numerator, denominator)) = rational_function_from_expr(expr.clone(), var.to_string()) else {
+        return vec![];
+    };
+    let mut result = vec![];
+    if let (Some(mut numerator_factors), Some(mut denominator_factors)) = (exact_factors(&numerator), exact_factors(&denominator)) {
+        // Gemeinsame Faktoren kürzen
+        for (factor, multiplicity) in numerator_factors.iter_mut() {
+            if let Some(other) = denominator_factors.iter_mut().find(|(other, _)| other == factor) {
+                let common = (*multiplicity).min(other.1);
+                *multiplicity -= common;
+                other.1 -= common;
+            }
+        }
+        numerator_factors.retain(|(_, multiplicity)| *multiplicity > 0);
+        denominator_factors.retain(|(_, multiplicity)| *multiplicity > 0);
+        let leading = numerator.leading_coefficient() / denominator.leading_coefficient();
+
+        let mut factors: Vec<(Expr, f64)> = vec![];
+        for (factor, multiplicity) in &numerator_factors {
+            factors.push((factor.to_expr(var.to_string()), *multiplicity as f64));
+        }
+        for (factor, multiplicity) in &denominator_factors {
+            factors.push((factor.to_expr(var.to_string()), -(*multiplicity as f64)));
+        }
+        result.push(build_sum(vec![(leading, build_product(factors))]));
+
+        let expand_factors = |factors: &[(Polynomial, usize)]| {
+            factors.iter().fold(Polynomial::constant(1.0), |acc, (factor, multiplicity)| acc.mul(&factor.pow(*multiplicity)))
+        };
+        let cancelled_numerator = expand_factors(&numerator_factors).scale(leading).to_expr(var.to_string());
+        let cancelled_denominator = expand_factors(&denominator_factors).to_expr(var.to_string());
+        result.push(simplify(
+            Expr::BinaryOp(BinaryOpKind::Div, Box::new(cancelled_numerator), Box::new(cancelled_denominator)),
+            false,
+        ));
+    }
+    if denominator.degree() > 0 {
+        if let Ok(decomposition) = apart(expr.clone(), var.to_string()) {
+            result.push(decomposition);
+        }
+    }
+    result
+}
+
+// Zerlegt ein Polynom in reelle Faktoren, aber nur, wenn alle Koeffizienten einfache Brüche sind.
+// Faktoren wie x - 1.4142 wären keine Vereinfachung.
+fn exact_factors(polynomial: &Polynomial) -> Option<Vec<(Polynomial, usize)>> {
+    if polynomial.is_zero() {
+        return None;
+    }
+    let factors = polynomial.real_factors();
+    let exact = factors
+        .iter()
+        .all(|(factor, _)| factor.coeffs.iter().all(|c| rational_approximation(*c, 1000).is_some()));
+    exact.then_some(factors)
+}
+
+/// Sammelt die freien Variablen eines Ausdrucks, ohne Konstanten und Funktionsnamen.
+pub fn variables(expr: &Expr) -> Vec<String> {
+    fn visit(expr: &Expr, result: &mut Vec<String>) {
+        match expr {
+            Expr::Var(v) if v == "pi" || v == "e" => {}
+            Expr::Var(v) => {
+                if !result.contains(v) {
+                    result.push(v.clone());
+                }
+            }
+            Expr::Number(_) => {}
+            Expr::BinaryOp(_, left, right) => {
+                visit(left, result);
+                visit(right, result);
+            }
+            Expr::UnaryOp(_, inner) => visit(inner, result),
+            Expr::Call(_, args) => args.iter().for_each(|arg| visit(arg, result)),
+            Expr::Derivative(_, _) => {}
+            Expr::Matrix(rows) => rows.iter().flatten().for_each(|entry| visit(entry, result)),
+            Expr::Equation(left, right) => {
+                visit(left, result);
+                visit(right, result);
+            }
+        }
+    }
+    let mut result = vec![];
+    visit(expr, &mut result);
+    result
+}
+
+fn node_count(expr: &Expr) -> usize {
+    match expr {
+        Expr::Number(_) | Expr::Var(_) => 1,
+        Expr::BinaryOp(_, left, right) => 1 + node_count(left) + node_count(right),
+        Expr::UnaryOp(_, inner) => 1 + node_count(inner),
+        Expr::Call(func, args) => 1 + node_count(func) + args.iter().map(node_count).sum::<usize>(),
+        Expr::Derivative(func, indices) => indices.len() + node_count(func),
+        Expr::Matrix(rows) => 1 + rows.iter().flatten().map(node_count).sum::<usize>(),
+        Expr::Equation(left, right) => 1 + node_count(left) + node_count(right),
+    }
+}
+
+fn leaf_count(expr: &Expr) -> usize {
+    match expr {
+        Expr::Number(_) | Expr::Var(_) => 1,
+        Expr::BinaryOp(_, left, right) => leaf_count(left) + leaf_count(right),
+        Expr::UnaryOp(_, inner) => leaf_count(inner),
+        Expr::Call(_, args) => args.iter().map(leaf_count).sum(),
+        Expr::Derivative(_, _) => 0,
+        Expr::Matrix(rows) => rows.iter().flatten().map(leaf_count).sum(),
+        Expr::Equation(left, right) => leaf_count(left) + leaf_count(right),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_complexity() {
+        let expr = parse("x^2 + sin(x)");
+        assert_eq!(complexity(&expr, Measure::NodeCount), 7);
+        assert_eq!(complexity(&expr, Measure::LeafCount), 3);
+        assert_eq!(complexity(&expr, Measure::LatexLength), "x^{2}+\\sin\\left(x\\right)".len());
+    }
+
+    #[test]
+    fn test_simplest_form() {
+        // Kürzen: (x^2 - 1)/(x - 1) = x + 1
+        assert_eq!(render_latex(&simplest_form(parse("(x^2 - 1)/(x - 1)"), Measure::NodeCount)), "x+1");
+        // Trigonometrie: sin(x)^2 + cos(x)^2 = 1
+        assert_eq!(simplest_form(parse("sin(x)^2 + cos(x)^2"), Measure::NodeCount), Expr::Number(1.0));
+        // Faktorisieren: x^2 + 2x + 1 = (x+1)^2
+        assert_eq!(render_latex(&simplest_form(parse("x^2 + 2*x + 1"), Measure::NodeCount)), "(x+1)^{2}");
+    }
+}
@@ -1,48 +1,115 @@
 use crate::parser::{BinaryOpKind, Expr};
 use crate::parser::Expr::Number;
+use crate::render::render_derivative_operator;
+use crate::simplify::{collect, simplify};
+use crate::steps::{self, Rule};
 
 // Die Hauptfunktion zur Ableitung eines Ausdrucks nach einer Variablen.
 pub fn differentiate(expr: Expr, var: String) -> Expr {
+    // Bei aktiver Aufzeichnung wird jede angewendete Regel als Schritt festgehalten
+    let step = steps::begin(|| render_derivative_operator(&[(var.clone(), 1)]), &expr);
+    let (rule, result) = differentiate_node(expr, var);
+    steps::finish(step, rule, &result);
+    result
+}
+
+// Leitet einen Knoten ab und gibt die dabei angewendete Regel mit zurück
+fn differentiate_node(expr: Expr, var: String) -> (Rule, Expr) {
     match expr {
-        Expr::Number(_) => Expr::Number(0.0), // Konstante Zahlen haben immer die Ableitung 0
+        Expr::Number(_) => (Rule::Constant, Expr::Number(0.0)), // Konstante Zahlen haben immer die Ableitung 0
 
         // Die Ableitung einer Variablen ist 1, wenn sie mit der gesuchten Variablen übereinstimmt,
         // andernfalls ist sie 0 (da sie dann als Konstante betrachtet wird)
         Expr::Var(v) => {
             if v == var {
-                Expr::Number(1.0)
+                (Rule::Identity, Expr::Number(1.0))
             } else {
-                Expr::Number(0.0)
+                (Rule::Constant, Expr::Number(0.0))
             }
         }
         // Differentiation für binäre Operationen wie +, -, *, /
         Expr::BinaryOp(op, left, right) => diff_binary_op(op, *left, *right, var),
 
         // Differentiation für unäre Operationen (wie Negation)
-        Expr::UnaryOp(op, expr) => diff_unary_op(op, *expr, var),
+        Expr::UnaryOp(op, expr) => (Rule::Negation, diff_unary_op(op, *expr, var)),
 
         // Differentiation für Funktionsaufrufe
         Expr::Call(expr, args) => diff_function(*expr, args, var),
-        _ => panic!("Not implemented"),
+
+        // Eine nicht angewendete Ableitung ist eine Funktion und hängt von keiner Variablen ab
+        Expr::Derivative(_, _) => (Rule::Constant, Expr::Number(0.0)),
+
+        // Matrizen werden eintragsweise abgeleitet
+        Expr::Matrix(rows) => (
+            Rule::Componentwise,
+            Expr::Matrix(
+                rows.into_iter()
+                    .map(|row| row.into_iter().map(|entry| differentiate(entry, var.clone())).collect())
+                    .collect(),
+            ),
+        ),
+
+        // Gleichungen werden auf beiden Seiten abgeleitet
+        Expr::Equation(left, right) => (
+            Rule::Componentwise,
+            Expr::Equation(Box::new(differentiate(*left, var.clone())), Box::new(differentiate(*right, var))),
+        ),
+    }
+}
+
+/// Leitet mehrfach und nach mehreren Variablen ab, z.B. `[("x", 2), ("y", 1)]` für ∂³/∂x²∂y.
+/// Nach jedem Schritt werden gleichartige Terme zusammengefasst, damit der Ausdruck nicht unnötig wächst.
+pub fn differentiate_n(expr: Expr, orders: &[(String, usize)]) -> Expr {
+    let mut result = collect(simplify(expr, false));
+    for (var, order) in orders {
+        for _ in 0..*order {
+            result = collect(simplify(differentiate(result, var.clone()), false));
+        }
     }
+    result
 }
 
-fn diff_binary_op(op: crate::parser::BinaryOpKind, left: Expr, right: Expr, var: String) -> Expr {
+/// Liest Ableitungsordnungen wie `x^2, y` oder `x, x, y`. Direkt wiederholte Variablen werden zusammengefasst.
+pub fn parse_orders(spec: &str) -> Result<Vec<(String, usize)>, String> {
+    let mut orders: Vec<(String, usize)> = vec![];
+    for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let (var, order) = match part.split_once('^') {
+            Some((var, order)) => {
+                let order = order.trim().parse::<usize>().map_err(|_| format!("Error: Invalid derivative order: {}", part))?;
+                (var.trim(), order)
+            }
+            None => (part, 1),
+        };
+        if var.is_empty() || !var.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("Error: Invalid variable: {}", var));
+        }
+        match orders.last_mut() {
+            Some((last, count)) if last == var => *count += order,
+            _ => orders.push((var.to_string(), order)),
+        }
+    }
+    if orders.iter().all(|(_, order)| *order == 0) {
+        return Err("Error: Expected at least one variable to differentiate by".to_string());
+    }
+    Ok(orders)
+}
+
+fn diff_binary_op(op: crate::parser::BinaryOpKind, left: Expr, right: Expr, var: String) -> (Rule, Expr) {
     match op {
         // Ableitung von Addition und Subtraktion erfolgt komponentenweise
-        crate::parser::BinaryOpKind::Add => Expr::BinaryOp(
+        crate::parser::BinaryOpKind::Add => (Rule::Sum, Expr::BinaryOp(
             crate::parser::BinaryOpKind::Add,
             Box::new(differentiate(left, var.clone())),
             Box::new(differentiate(right, var)),
-        ),
-        crate::parser::BinaryOpKind::Sub => Expr::BinaryOp(
+        )),
+        crate::parser::BinaryOpKind::Sub => (Rule::Difference, Expr::BinaryOp(
             crate::parser::BinaryOpKind::Sub,
             Box::new(differentiate(left, var.clone())),
             Box::new(differentiate(right, var)),
-        ),
+        )),
 
         // Ableitung von Multiplikation mit Produktregel
-        crate::parser::BinaryOpKind::Mul => Expr::BinaryOp(
+        crate::parser::BinaryOpKind::Mul => (Rule::Product, Expr::BinaryOp(
             crate::parser::BinaryOpKind::Add,
             Box::new(Expr::BinaryOp(
                 crate::parser::BinaryOpKind::Mul,
@@ -54,7 +121,7 @@ fn diff_binary_op(op: crate::parser::BinaryOpKind, left: Expr, right: Expr, var:
                 Box::new(left.clone()),
                 Box::new(differentiate(right, var)),
             )),
-        ),
+        )),
 
         // Ableitung von Division mit Quotientenregel
         crate::parser::BinaryOpKind::Div => {
@@ -80,59 +147,73 @@ fn diff_binary_op(op: crate::parser::BinaryOpKind, left: Expr, right: Expr, var:
                 Box::new(right.clone()),
                 Box::new(right.clone()),
             );
-            Expr::BinaryOp(crate::parser::BinaryOpKind::Div, Box::new(numerator), Box::new(denominator))
+            (Rule::Quotient, Expr::BinaryOp(crate::parser::BinaryOpKind::Div, Box::new(numerator), Box::new(denominator)))
         },
 
         // Ableitung von Potenzfunktionen
         crate::parser::BinaryOpKind::Pow => {
-            // Ableitung von f(x)^g(x) mit der Regel:
-            // General case: (f(x)^g(x))' = g(x) f(x)^(g(x)-1) f'(x) + f(x)^g(x) ln(f(x)) g'(x)
-            let base = left.clone();
-            let exponent = right.clone();
-            let base_diff = differentiate(base.clone(), var.clone());
-            let exponent_diff = differentiate(exponent.clone(), var.clone());
-
-            let term1 = Expr::BinaryOp(
-                crate::parser::BinaryOpKind::Mul,
-                Box::new(exponent.clone()), // g(x)
-                Box::new(Expr::BinaryOp(
-                    crate::parser::BinaryOpKind::Mul,
+            let base_diff = differentiate(left.clone(), var.clone());
+            if !depends_on(&right, &var) {
+                // Konstanter Exponent: (f^n)' = n f^(n-1) f'
+                let exponent = match right {
+                    Expr::Number(n) => Expr::Number(n - 1.0),
+                    ref exponent => Expr::BinaryOp(BinaryOpKind::Sub, Box::new(exponent.clone()), Box::new(Expr::Number(1.0))),
+                };
+                return (Rule::Power, Expr::BinaryOp(
+                    BinaryOpKind::Mul,
                     Box::new(Expr::BinaryOp(
-                        crate::parser::BinaryOpKind::Pow,
-                        Box::new(base.clone()),
-                        Box::new(Expr::BinaryOp(
-                            crate::parser::BinaryOpKind::Sub,
-                            Box::new(exponent.clone()),
-                            Box::new(Expr::Number(1.0)),
-                        )),
+                        BinaryOpKind::Mul,
+                        Box::new(right),
+                        Box::new(Expr::BinaryOp(BinaryOpKind::Pow, Box::new(left), Box::new(exponent))),
                     )),
                     Box::new(base_diff),
-                )),
-            );
-
-            let term2 = Expr::BinaryOp(
-                crate::parser::BinaryOpKind::Mul,
+                ));
+            }
+            let exponent_diff = differentiate(right.clone(), var.clone());
+            let power = Expr::BinaryOp(BinaryOpKind::Pow, Box::new(left.clone()), Box::new(right.clone()));
+            if !depends_on(&left, &var) {
+                // Konstante Basis: (a^g)' = a^g ln(a) g', für a = e entfällt ln(a)
+                let factor = match left {
+                    Expr::Var(ref name) if name == "e" => power,
+                    _ => Expr::BinaryOp(BinaryOpKind::Mul, Box::new(power), Box::new(ln(left))),
+                };
+                return (Rule::Exponential, Expr::BinaryOp(BinaryOpKind::Mul, Box::new(factor), Box::new(exponent_diff)));
+            }
+            // Allgemeiner Fall: (f^g)' = f^g (g' ln(f) + g f'/f)
+            (Rule::GeneralPower, Expr::BinaryOp(
+                BinaryOpKind::Mul,
+                Box::new(power),
                 Box::new(Expr::BinaryOp(
-                    crate::parser::BinaryOpKind::Mul,
-                    Box::new(Expr::BinaryOp(
-                        crate::parser::BinaryOpKind::Pow,
-                        Box::new(base.clone()),
-                        Box::new(exponent.clone()),
-                    )),
+                    BinaryOpKind::Add,
+                    Box::new(Expr::BinaryOp(BinaryOpKind::Mul, Box::new(exponent_diff), Box::new(ln(left.clone())))),
                     Box::new(Expr::BinaryOp(
-                        crate::parser::BinaryOpKind::Mul,
-                        Box::new(Expr::Var("ln".to_string())),
-                        Box::new(base.clone()),
+                        BinaryOpKind::Div,
+                        Box::new(Expr::BinaryOp(BinaryOpKind::Mul, Box::new(right), Box::new(base_diff))),
+                        Box::new(left),
                     )),
                 )),
-                Box::new(exponent_diff),
-            );
-
-            Expr::BinaryOp(crate::parser::BinaryOpKind::Add, Box::new(term1), Box::new(term2))
+            ))
         }
     }
 }
 
+fn ln(argument: Expr) -> Expr {
+    Expr::Call(Box::new(Expr::Var("ln".to_string())), vec![argument])
+}
+
+/// Prüft, ob ein Ausdruck von der Variablen `var` abhängt.
+pub fn depends_on(expr: &Expr, var: &str) -> bool {
+    match expr {
+        Expr::Var(v) => v == var,
+        Expr::Number(_) => false,
+        Expr::BinaryOp(_, left, right) => depends_on(left, var) || depends_on(right, var),
+        Expr::UnaryOp(_, inner) => depends_on(inner, var),
+        Expr::Call(_, args) => args.iter().any(|arg| depends_on(arg, var)),
+        Expr::Derivative(_, _) => false,
+        Expr::Matrix(rows) => rows.iter().flatten().any(|entry| depends_on(entry, var)),
+        Expr::Equation(left, right) => depends_on(left, var) || depends_on(right, var),
+    }
+}
 
 fn diff_unary_op(op: crate::parser::UnaryOpKind, expr: Expr, var: String) -> Expr {
     match op {
@@ -140,57 +221,270 @@ fn diff_unary_op(op: crate::parser::UnaryOpKind, expr: Expr, var: String) -> Exp
     }
 }
 
-fn diff_function(expr: Expr, args: Vec<Expr>, var: String) -> Expr {
-    match expr.clone() {
-        Expr::Var(v) => {
-            let mut result = Number(0.0);
-            for i in 0..args.len() {
-                let arg = args[i].clone();
-                let mut f_prime;
-                if is_elementary_function(&v) {
-                    f_prime = differentiate_elementary_function(&v, i, var.clone());
-                } else {
-                    f_prime = differentiate(expr.clone(), var.clone());
-                }
-                f_prime = Expr::Call(Box::new(f_prime), args.clone());
-                result = Expr::BinaryOp(
-                    crate::parser::BinaryOpKind::Add,
-                    Box::new(result),
-                    Box::new(Expr::BinaryOp(
-                        crate::parser::BinaryOpKind::Mul,
-                        Box::new(f_prime.clone()),
-                        Box::new(differentiate(arg, var.clone())),
-                    )),
-                );
-            }
-            result
+fn diff_function(expr: Expr, args: Vec<Expr>, var: String) -> (Rule, Expr) {
+    // Logarithmus zur Basis b: log(b, x) = ln(x) / ln(b)
+    if matches!(expr, Expr::Var(ref v) if v == "log") && args.len() == 2 {
+        return (Rule::Logarithm, differentiate(binary(BinaryOpKind::Div, ln(args[1].clone()), ln(args[0].clone())), var));
+    }
+    // Elementare Funktionen haben genau ein Argument, sonst bleibt die Ableitung wie bei
+    // unbekannten Funktionen unausgewertet, z.B. ∂_1 sin(x, y)
+    let elementary = match &expr {
+        Expr::Var(name) if is_elementary_function(name) && args.len() == 1 => Some(name.as_str()),
+        _ => None,
+    };
+    let rule = match (&expr, args.as_slice()) {
+        (Expr::Var(name), [Expr::Var(arg)]) if *arg == var && is_elementary_function(name) => Rule::Elementary(name.clone()),
+        (_, [_]) => Rule::Chain,
+        _ => Rule::MultivariableChain,
+    };
+    // Kettenregel: d/dx f(g_1, ..., g_n) = Σ ∂_i f(g_1, ..., g_n) · g_i'
+    let mut result = Number(0.0);
+    for (i, arg) in args.iter().enumerate() {
+        let f_prime = match elementary {
+            Some(name) => elementary_derivative(name),
+            None => partial_derivative(expr.clone(), i),
+        };
+        result = binary(
+            BinaryOpKind::Add,
+            result,
+            binary(BinaryOpKind::Mul, Expr::Call(Box::new(f_prime), args.clone()), differentiate(arg.clone(), var.clone())),
+        );
+    }
+    (rule, result)
+}
+
+/// Unausgewertete partielle Ableitung ∂_i f einer unbekannten oder benutzerdefinierten Funktion.
+/// Gemischte Ableitungen werden nach dem Satz von Schwarz sortiert, damit ∂_1 ∂_2 f = ∂_2 ∂_1 f.
+pub fn partial_derivative(func: Expr, index: usize) -> Expr {
+    match func {
+        Expr::Derivative(inner, mut indices) => {
+            indices.push(index);
+            indices.sort();
+            Expr::Derivative(inner, indices)
         }
-        _ => panic!("Not implemented"),
+        func => Expr::Derivative(Box::new(func), vec![index]),
+    }
+}
+
+/// Name einer unausgewerteten Ableitung für Fehlermeldungen, z.B. `f''` oder `∂_1∂_2 f`.
+pub fn derivative_name(func: &Expr, indices: &[usize]) -> String {
+    let name = match func {
+        Expr::Var(name) => name.clone(),
+        func => crate::render::render_latex(func),
+    };
+    if indices.iter().all(|&i| i == 0) {
+        format!("{}{}", name, "'".repeat(indices.len()))
+    } else {
+        let partials: String = indices.iter().map(|i| format!("∂_{}", i + 1)).collect();
+        format!("{} {}", partials, name)
     }
 }
 
+/// Alle elementaren Funktionen mit einem Argument. `log(b, x)` hat zwei Argumente und wird separat behandelt.
+pub const ELEMENTARY_FUNCTIONS: &[&str] = &[
+    "id", "exp", "ln", "sqrt", "sin", "cos", "tan", "cot", "sec", "csc", "asin", "acos", "atan", "sinh", "cosh",
+    "tanh", "asinh", "acosh", "atanh", "abs", "sign", "erf", "gamma", "digamma",
+];
+
 // Prüft, ob eine Funktion elementar ist (z.B. exp, ln, sin, cos)
 pub fn is_elementary_function(name: &str) -> bool {
-    name == "exp" || name == "ln" ||  name == "sin" || name == "cos" || name == "id"
+    ELEMENTARY_FUNCTIONS.contains(&name)
+}
+
+// Hilfsfunktionen für die punktfreien Ableitungen, `id` steht für das Argument
+fn function(name: &str) -> Expr {
+    Expr::Var(name.to_string())
+}
+
+fn binary(op: BinaryOpKind, left: Expr, right: Expr) -> Expr {
+    Expr::BinaryOp(op, Box::new(left), Box::new(right))
+}
+
+fn neg(expr: Expr) -> Expr {
+    Expr::UnaryOp(crate::parser::UnaryOpKind::Neg, Box::new(expr))
+}
+
+// 1 / (a + b id^2)^(1/2), die gemeinsame Form der Ableitungen von asin, asinh und acosh
+fn inverse_sqrt_quadratic(a: f64, b: f64) -> Expr {
+    let quadratic = binary(
+        BinaryOpKind::Add,
+        Number(a),
+        binary(BinaryOpKind::Mul, Number(b), binary(BinaryOpKind::Pow, function("id"), Number(2.0))),
+    );
+    binary(BinaryOpKind::Div, Number(1.0), binary(BinaryOpKind::Pow, quadratic, Number(0.5)))
 }
 
-// Ableitungen von elementaren Funktionen
-fn differentiate_elementary_function(name: &str, arg_index: usize, var: String) -> Expr {
+/// Punktfreie Ableitung f' einer elementaren Funktion, z.B. `cos` für `sin`.
+/// Die automatische Differentiation wertet dieselbe Tabelle aus wie das symbolische Ableiten.
+pub(crate) fn elementary_derivative(name: &str) -> Expr {
     match name {
+        "id" => Number(1.0),
         "exp" => Expr::Var("exp".to_string()),
-        "ln" => {
-            if arg_index == 0 {
-                Expr::BinaryOp(
-                    crate::parser::BinaryOpKind::Div,
-                    Box::new(Number(1.0)),
-                    Box::new(Expr::Var("id".to_string())),
-                )
-            } else {
-                panic!("log function can only have one argument");
-            }
-        },
+        "ln" => Expr::BinaryOp(
+            crate::parser::BinaryOpKind::Div,
+            Box::new(Number(1.0)),
+            Box::new(Expr::Var("id".to_string())),
+        ),
         "sin" => Expr::Var("cos".to_string()),
         "cos" => Expr::UnaryOp(crate::parser::UnaryOpKind::Neg, Box::new(Expr::Var("sin".to_string()))),
-        _ => panic!("Not implemented"),
+        // tan'(x) = 1 + tan(x)^2
+        "tan" => binary(BinaryOpKind::Add, Number(1.0), binary(BinaryOpKind::Pow, function("tan"), Number(2.0))),
+        // cot'(x) = -(1 + cot(x)^2)
+        "cot" => neg(binary(BinaryOpKind::Add, Number(1.0), binary(BinaryOpKind::Pow, function("cot"), Number(2.0)))),
+        // sec'(x) = sec(x) tan(x), csc'(x) = -csc(x) cot(x)
+        "sec" => binary(BinaryOpKind::Mul, function("sec"), function("tan")),
+        "csc" => neg(binary(BinaryOpKind::Mul, function("csc"), function("cot"))),
+        // asin'(x) = 1 / sqrt(1 - x^2), acos'(x) = -1 / sqrt(1 - x^2)
+        "asin" => inverse_sqrt_quadratic(1.0, -1.0),
+        "acos" => neg(inverse_sqrt_quadratic(1.0, -1.0)),
+        // atan'(x) = 1 / (1 + x^2)
+        "atan" => binary(
+            BinaryOpKind::Div,
+            Number(1.0),
+            binary(BinaryOpKind::Add, Number(1.0), binary(BinaryOpKind::Pow, function("id"), Number(2.0))),
+        ),
+        "sinh" => function("cosh"),
+        "cosh" => function("sinh"),
+        // tanh'(x) = 1 - tanh(x)^2
+        "tanh" => binary(BinaryOpKind::Sub, Number(1.0), binary(BinaryOpKind::Pow, function("tanh"), Number(2.0))),
+        // asinh'(x) = 1 / sqrt(x^2 + 1), acosh'(x) = 1 / sqrt(x^2 - 1), atanh'(x) = 1 / (1 - x^2)
+        "asinh" => inverse_sqrt_quadratic(1.0, 1.0),
+        "acosh" => inverse_sqrt_quadratic(-1.0, 1.0),
+        "atanh" => binary(
+            BinaryOpKind::Div,
+            Number(1.0),
+            binary(BinaryOpKind::Sub, Number(1.0), binary(BinaryOpKind::Pow, function("id"), Number(2.0))),
+        ),
+        // sqrt'(x) = 1 / (2 sqrt(x))
+        "sqrt" => binary(BinaryOpKind::Div, Number(1.0), binary(BinaryOpKind::Mul, Number(2.0), function("sqrt"))),
+        // |x|' = sign(x), sign'(x) = 0 außerhalb von 0
+        "abs" => function("sign"),
+        "sign" => Number(0.0),
+        // erf'(x) = 2/sqrt(pi) exp(-x^2)
+        "erf" => binary(
+            BinaryOpKind::Mul,
+            binary(BinaryOpKind::Div, Number(2.0), binary(BinaryOpKind::Pow, function("pi"), Number(0.5))),
+            Expr::Call(Box::new(function("exp")), vec![neg(binary(BinaryOpKind::Pow, function("id"), Number(2.0)))]),
+        ),
+        // Γ'(x) = Γ(x) ψ(x)
+        "gamma" => binary(BinaryOpKind::Mul, function("gamma"), function("digamma")),
+        // ψ' (Trigammafunktion) ist nicht elementar und bleibt wie jede Funktion ohne Tabelleneintrag unausgewertet
+        _ => partial_derivative(function(name), 0),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate::{evaluate, Environment};
+    use crate::parser::parse;
+
+    // Vergleicht die symbolische Ableitung mit dem zentralen Differenzenquotienten
+    fn assert_matches_finite_difference(source: &str, points: &[f64]) {
+        let expr = parse(source);
+        let derivative = differentiate(expr.clone(), "x".to_string());
+        let mut env = Environment::new();
+        let mut at = |expr: &Expr, x: f64| {
+            env.set_variable("x".to_string(), x);
+            evaluate(expr, &env).unwrap()
+        };
+        for &x in points {
+            let h = 1e-6;
+            let numeric = (at(&expr, x + h) - at(&expr, x - h)) / (2.0 * h);
+            let symbolic = at(&derivative, x);
+            assert!((numeric - symbolic).abs() < 1e-5 * (1.0 + numeric.abs()), "{}: {} != {} at x = {}", source, symbolic, numeric, x);
+        }
+    }
+
+    #[test]
+    fn test_power_rule_constant_exponent() {
+        assert_eq!(
+            differentiate(parse("x^3"), "x".to_string()),
+            Expr::BinaryOp(
+                BinaryOpKind::Mul,
+                Box::new(Expr::BinaryOp(
+                    BinaryOpKind::Mul,
+                    Box::new(Number(3.0)),
+                    Box::new(Expr::BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Var("x".to_string())), Box::new(Number(2.0)))),
+                )),
+                Box::new(Number(1.0)),
+            )
+        );
+        assert_matches_finite_difference("x^3", &[-2.0, 0.5, 3.0]);
+        assert_matches_finite_difference("(x^2 + 1)^(1/2)", &[-1.5, 0.0, 2.0]);
+        assert_matches_finite_difference("x^(pi/2)", &[0.5, 2.0]);
+    }
+
+    #[test]
+    fn test_power_rule_constant_base() {
+        assert_matches_finite_difference("2^x", &[-1.0, 0.0, 1.5]);
+        assert_matches_finite_difference("e^(x^2)", &[-1.0, 0.3]);
+        assert_matches_finite_difference("3^sin(x)", &[0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_power_rule_general() {
+        assert_matches_finite_difference("x^x", &[0.5, 1.0, 2.0]);
+        assert_matches_finite_difference("(x^2 + 1)^sin(x)", &[-1.0, 0.7, 2.5]);
+        assert_matches_finite_difference("ln(x) * x^2 / cos(x)", &[0.4, 1.2]);
+    }
+
+    #[test]
+    fn test_elementary_function_library() {
+        assert_matches_finite_difference("tan(x) + cot(x)", &[0.4, 1.1]);
+        assert_matches_finite_difference("sec(x^2) * csc(x)", &[0.5, 1.0]);
+        assert_matches_finite_difference("asin(x) - acos(x/2) + atan(x^2)", &[-0.5, 0.3]);
+        assert_matches_finite_difference("sinh(x) * cosh(x) + tanh(2*x)", &[-1.0, 0.5]);
+        assert_matches_finite_difference("asinh(x) + acosh(x + 2) + atanh(x/3)", &[-0.5, 0.8]);
+        assert_matches_finite_difference("sqrt(x^2 + 1) + log(2, x)", &[0.5, 3.0]);
+        assert_matches_finite_difference("abs(sin(x)) + sign(x) * x", &[-1.0, 2.0]);
+        assert_matches_finite_difference("erf(x^2) + gamma(x)", &[0.7, 2.5]);
+        assert_matches_finite_difference("id(x^2) * id(sin(x))", &[-0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_unknown_function_chain_rule() {
+        use crate::render::render_latex;
+        let derivative = simplify(differentiate(parse("f(g(x))"), "x".to_string()), false);
+        assert_eq!(render_latex(&derivative), "f'\\left(g\\left(x\\right)\\right) \\cdot g'\\left(x\\right)");
+        let derivative = simplify(differentiate(parse("h(x, y)"), "y".to_string()), false);
+        assert_eq!(render_latex(&derivative), "\\partial_{2} h\\left(x, y\\right)");
+        // Gemischte Ableitungen sind unabhängig von der Reihenfolge
+        let mixed = |first: &str, second: &str| {
+            simplify(differentiate(differentiate(parse("h(x, y)"), first.to_string()), second.to_string()), false)
+        };
+        assert_eq!(mixed("x", "y"), mixed("y", "x"));
+        // ψ' bleibt als unausgewertete Ableitung stehen
+        let derivative = simplify(differentiate(parse("digamma(x)"), "x".to_string()), false);
+        assert_eq!(render_latex(&derivative), "\\psi'\\left(x\\right)");
+        // Elementare Funktionen mit falscher Argumentanzahl werden nicht ausgewertet
+        let derivative = simplify(differentiate(parse("ln(x, y)"), "y".to_string()), false);
+        assert_eq!(render_latex(&derivative), "\\partial_{2} \\ln\\left(x, y\\right)");
+        let derivative = simplify(differentiate(parse("sin(x, y)"), "x".to_string()), false);
+        assert_eq!(render_latex(&derivative), "\\partial_{1} \\sin\\left(x, y\\right)");
+    }
+
+    #[test]
+    fn test_derivative_of_user_defined_function() {
+        let mut env = Environment::new();
+        env.define_function("f".to_string(), vec!["t".to_string()], parse("t^3"));
+        env.set_variable("x".to_string(), 2.0);
+        // d/dx f(x^2) = f'(x^2) 2x = 3 x^4 2x
+        let derivative = differentiate(parse("f(x^2)"), "x".to_string());
+        assert_eq!(evaluate(&derivative, &env), Ok(192.0));
+        assert!(evaluate(&differentiate(parse("g(x)"), "x".to_string()), &env).is_err());
+    }
+
+    #[test]
+    fn test_differentiate_n() {
+        use crate::render::render_latex;
+        let orders = parse_orders("x^3").unwrap();
+        assert_eq!(render_latex(&differentiate_n(parse("x^4 + sin(x)"), &orders)), "24 \\cdot x-\\cos\\left(x\\right)");
+        let orders = parse_orders("x, y").unwrap();
+        assert_eq!(orders, vec![("x".to_string(), 1), ("y".to_string(), 1)]);
+        assert_eq!(render_latex(&differentiate_n(parse("x^2*y^3"), &orders)), "6 \\cdot x \\cdot y^{2}");
+        assert_eq!(parse_orders("x, x"), Ok(vec![("x".to_string(), 2)]));
+        assert!(parse_orders("x^a").is_err());
+        assert!(parse_orders("").is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::constants::{E, PI};
+use crate::differentiate::{derivative_name, differentiate, is_elementary_function};
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Fehler bei der numerischen Auswertung eines Ausdrucks.
+#[derive(Debug, PartialEq, Clone)]
+pub enum EvalError {
+    /// Die Variable hat in der Umgebung keinen Wert
+    UnknownVariable(String),
+    /// Die Funktion ist weder elementar noch in der Umgebung definiert
+    UnknownFunction(String),
+    /// Die Funktion wurde mit der falschen Anzahl an Argumenten aufgerufen
+    WrongArgumentCount { function: String, expected: usize, found: usize },
+    /// Division durch 0, auch als negative Potenz von 0
+    DivisionByZero,
+    /// Das Argument liegt außerhalb des Definitionsbereichs, z.B. `ln(-1)`
+    Domain { function: String, argument: f64 },
+    /// Matrizen, Vektoren und Gleichungen haben keinen Zahlenwert
+    NotScalar,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::UnknownVariable(name) => write!(f, "Error: Variable {} has no value", name),
+            EvalError::UnknownFunction(name) => write!(f, "Error: Unknown function: {}", name),
+            EvalError::WrongArgumentCount { function, expected, found } => {
+                write!(f, "Error: {} expects {} argument(s), got {}", function, expected, found)
+            }
+            EvalError::DivisionByZero => write!(f, "Error: Division by zero"),
+            EvalError::Domain { function, argument } => {
+                write!(f, "Error: {} is not defined for {}", function, argument)
+            }
+            EvalError::NotScalar => write!(f, "Error: Expected a number, got a matrix or an equation"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Die Umgebung für die Auswertung: Werte von Variablen und benutzerdefinierte Funktionen.
+/// Die Konstanten `pi` und `e` sind bereits gesetzt.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    variables: HashMap<String, f64>,
+    functions: HashMap<String, (Vec<String>, Expr)>,
+}
+
+impl Environment {
+    pub fn new() -> Environment {
+        let mut variables = HashMap::new();
+        variables.insert("pi".to_string(), PI);
+        variables.insert("e".to_string(), E);
+        Environment { variables, functions: HashMap::new() }
+    }
+
+    /// Setzt den Wert einer Variablen.
+    pub fn set_variable(&mut self, name: String, value: f64) {
+        self.variables.insert(name, value);
+    }
+
+    /// Definiert eine Funktion, z.B. `f(x, y) = x^2 + y` mit den Parametern `["x", "y"]`.
+    pub fn define_function(&mut self, name: String, parameters: Vec<String>, body: Expr) {
+        self.functions.insert(name, (parameters, body));
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Environment {
+        Environment::new()
+    }
+}
+
+/// Wertet einen Ausdruck in der Umgebung `env` numerisch aus.
+/// Definitionslücken wie `ln(-1)` oder `1/0` werden als Fehler gemeldet statt als NaN.
+pub fn evaluate(expr: &Expr, env: &Environment) -> Result<f64, EvalError> {
+    match expr {
+        Expr::Number(x) => Ok(*x),
+        Expr::Var(name) => env.variables.get(name).copied().ok_or_else(|| EvalError::UnknownVariable(name.clone())),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => Ok(-evaluate(inner, env)?),
+        Expr::BinaryOp(op, left, right) => binary(*op, evaluate(left, env)?, evaluate(right, env)?),
+        Expr::Call(func, args) => {
+            let args = args.iter().map(|arg| evaluate(arg, env)).collect::<Result<Vec<f64>, EvalError>>()?;
+            apply(func, &args, env)
+        }
+        // Eine nicht angewendete Ableitung ist eine Funktion und hat keinen Zahlenwert
+        Expr::Derivative(func, indices) => Err(EvalError::UnknownVariable(derivative_name(func, indices))),
+        Expr::Matrix(_) | Expr::Equation(_, _) => Err(EvalError::NotScalar),
+    }
+}
+
+pub(crate) fn binary(op: BinaryOpKind, a: f64, b: f64) -> Result<f64, EvalError> {
+    match op {
+        BinaryOpKind::Add => Ok(a + b),
+        BinaryOpKind::Sub => Ok(a - b),
+        BinaryOpKind::Mul => Ok(a * b),
+        BinaryOpKind::Div if b == 0.0 => Err(EvalError::DivisionByZero),
+        BinaryOpKind::Div => Ok(a / b),
+        BinaryOpKind::Pow if a == 0.0 && b < 0.0 => Err(EvalError::DivisionByZero),
+        BinaryOpKind::Pow if a < 0.0 && b.fract() != 0.0 => Err(EvalError::Domain { function: "pow".to_string(), argument: a }),
+        BinaryOpKind::Pow => Ok(a.powf(b)),
+    }
+}
+
+// Wendet eine Funktion auf bereits ausgewertete Argumente an. Wie beim Ableiten können Funktionen
+// auch punktfrei zusammengesetzt sein, z.B. (1/id)(x) = 1/x
+fn apply(func: &Expr, args: &[f64], env: &Environment) -> Result<f64, EvalError> {
+    match func {
+        Expr::Number(c) => Ok(*c),
+        // Konstanten sind in punktfreien Ausdrücken konstante Funktionen
+        Expr::Var(name) if name == "pi" || name == "e" => evaluate(func, env),
+        Expr::Var(name) if name == "log" => match args {
+            [b, x] => log(*b, *x),
+            _ => Err(EvalError::WrongArgumentCount { function: name.clone(), expected: 2, found: args.len() }),
+        },
+        Expr::Var(name) => match env.functions.get(name) {
+            Some((parameters, body)) => {
+                if parameters.len() != args.len() {
+                    return Err(EvalError::WrongArgumentCount { function: name.clone(), expected: parameters.len(), found: args.len() });
+                }
+                let mut local = env.clone();
+                for (parameter, value) in parameters.iter().zip(args) {
+                    local.set_variable(parameter.clone(), *value);
+                }
+                evaluate(body, &local)
+            }
+            None => match args {
+                [arg] => elementary(name, *arg),
+                _ if is_elementary_function(name) => {
+                    Err(EvalError::WrongArgumentCount { function: name.clone(), expected: 1, found: args.len() })
+                }
+                _ => Err(EvalError::UnknownFunction(name.clone())),
+            },
+        },
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => Ok(-apply(inner, args, env)?),
+        Expr::BinaryOp(op, left, right) => binary(*op, apply(left, args, env)?, apply(right, args, env)?),
+        Expr::Call(outer, inner) => {
+            // Verkettung: f(g)(x) = f(g(x))
+            let inner = inner.iter().map(|g| apply(g, args, env)).collect::<Result<Vec<f64>, EvalError>>()?;
+            apply(outer, &inner, env)
+        }
+        // Ableitungen benutzerdefinierter Funktionen werden symbolisch aus dem Funktionsterm gebildet
+        Expr::Derivative(f, indices) => match &**f {
+            Expr::Var(name) if env.functions.contains_key(name) => {
+                let (parameters, body) = &env.functions[name];
+                if let Some(&index) = indices.iter().find(|&&index| index >= parameters.len()) {
+                    return Err(EvalError::WrongArgumentCount { function: name.clone(), expected: parameters.len(), found: index + 1 });
+                }
+                let derivative = indices
+                    .iter()
+                    .fold(body.clone(), |derivative, &index| differentiate(derivative, parameters[index].clone()));
+                let mut local = env.clone();
+                local.define_function(name.clone(), parameters.clone(), derivative);
+                apply(f, args, &local)
+            }
+            _ => Err(EvalError::UnknownFunction(derivative_name(f, indices))),
+        },
+        Expr::Matrix(_) | Expr::Equation(_, _) => Err(EvalError::NotScalar),
+    }
+}
+
+// Elementare Funktionen mit Prüfung des Definitionsbereichs
+pub(crate) fn elementary(name: &str, x: f64) -> Result<f64, EvalError> {
+    let domain_error = || EvalError::Domain { function: name.to_string(), argument: x };
+    match name {
+        "id" => Ok(x),
+        "exp" => Ok(x.exp()),
+        "ln" if x <= 0.0 => Err(domain_error()),
+        "ln" => Ok(x.ln()),
+        "sqrt" if x < 0.0 => Err(domain_error()),
+        "sqrt" => Ok(x.sqrt()),
+        "sin" => Ok(x.sin()),
+        "cos" => Ok(x.cos()),
+        "tan" | "sec" if near_pole(x.cos(), x) => Err(domain_error()),
+        "tan" => Ok(x.tan()),
+        "sec" => Ok(1.0 / x.cos()),
+        "cot" | "csc" if near_pole(x.sin(), x) => Err(domain_error()),
+        "cot" => Ok(1.0 / x.tan()),
+        "csc" => Ok(1.0 / x.sin()),
+        "asin" | "acos" if x.abs() > 1.0 => Err(domain_error()),
+        "asin" => Ok(x.asin()),
+        "acos" => Ok(x.acos()),
+        "atan" => Ok(x.atan()),
+        "sinh" => Ok(x.sinh()),
+        "cosh" => Ok(x.cosh()),
+        "tanh" => Ok(x.tanh()),
+        "asinh" => Ok(x.asinh()),
+        "acosh" if x < 1.0 => Err(domain_error()),
+        "acosh" => Ok(x.acosh()),
+        "atanh" if x.abs() >= 1.0 => Err(domain_error()),
+        "atanh" => Ok(x.atanh()),
+        "abs" => Ok(x.abs()),
+        "sign" if x == 0.0 => Ok(0.0),
+        "sign" => Ok(x.signum()),
+        "erf" => Ok(erf(x)),
+        "gamma" | "digamma" if x <= 0.0 && x.fract() == 0.0 => Err(domain_error()),
+        "gamma" => Ok(gamma(x)),
+        "digamma" => Ok(digamma(x)),
+        _ => Err(EvalError::UnknownFunction(name.to_string())),
+    }
+}
+
+// Nullstelle des Nenners von tan, sec, cot oder csc: Bei x = pi/2 ist cos(x) in Gleitkommazahlen
+// nicht genau 0, sondern nur so klein wie der Rundungsfehler von x, der mit |x| wächst
+fn near_pole(denominator: f64, x: f64) -> bool {
+    denominator.abs() <= 4.0 * f64::EPSILON * x.abs().max(1.0)
+}
+
+/// Logarithmus zur Basis `b`, definiert für `b > 0`, `b ≠ 1` und `x > 0`.
+pub(crate) fn log(b: f64, x: f64) -> Result<f64, EvalError> {
+    if b <= 0.0 || b == 1.0 {
+        return Err(EvalError::Domain { function: "log".to_string(), argument: b });
+    }
+    if x <= 0.0 {
+        return Err(EvalError::Domain { function: "log".to_string(), argument: x });
+    }
+    Ok(x.ln() / b.ln())
+}
+
+// Fehlerfunktion über die Reihe erf(x) = 2/sqrt(pi) exp(-x^2) Σ 2^n x^(2n+1) / (1·3·…·(2n+1)).
+// Alle Summanden sind positiv, es gibt also keine Auslöschung. Ab |x| = 6 ist erf(x) = ±1 in f64.
+fn erf(x: f64) -> f64 {
+    if x.abs() >= 6.0 {
+        return x.signum();
+    }
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 0.0;
+    while term.abs() > 1e-17 * sum.abs() {
+        n += 1.0;
+        term *= 2.0 * x * x / (2.0 * n + 1.0);
+        sum += term;
+    }
+    2.0 / PI.sqrt() * (-x * x).exp() * sum
+}
+
+// Gammafunktion mit der Lanczos-Näherung (g = 7) und dem Ergänzungssatz für x < 1/2
+fn gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        return PI / ((PI * x).sin() * gamma(1.0 - x));
+    }
+    // Ganzzahlige Argumente exakt als Fakultät
+    if x.fract() == 0.0 && x <= 171.0 {
+        return (1..x as u64).fold(1.0, |acc, k| acc * k as f64);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.0));
+    (2.0 * PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * sum
+}
+
+// Digammafunktion ψ = Γ'/Γ: Rekursion ψ(x) = ψ(x+1) - 1/x bis x ≥ 6, dann asymptotische Reihe
+fn digamma(x: f64) -> f64 {
+    if x < 0.5 {
+        return digamma(1.0 - x) - PI / (PI * x).tan();
+    }
+    let mut x = x;
+    let mut result = 0.0;
+    while x < 10.0 {
+        result -= 1.0 / x;
+        x += 1.0;
+    }
+    let inverse_square = 1.0 / (x * x);
+    result + x.ln() - 0.5 / x
+        - inverse_square * (1.0 / 12.0 - inverse_square * (1.0 / 120.0 - inverse_square * (1.0 / 252.0 - inverse_square * (1.0 / 240.0 - inverse_square / 132.0))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_evaluate() {
+        let mut env = Environment::new();
+        env.set_variable("x".to_string(), 2.0);
+        assert_eq!(evaluate(&parse("x^2 + 3*x + 1"), &env), Ok(11.0));
+        assert_eq!(evaluate(&parse("cos(pi)"), &env), Ok(-1.0));
+        assert_eq!(evaluate(&parse("exp(ln(x))"), &env).map(|v| (v - 2.0).abs() < 1e-12), Ok(true));
+        // Punktfreie Ableitung von ln: (1/id)(x)
+        let derivative = Expr::Call(
+            Box::new(Expr::BinaryOp(BinaryOpKind::Div, Box::new(Expr::Number(1.0)), Box::new(Expr::Var("id".to_string())))),
+            vec![Expr::Var("x".to_string())],
+        );
+        assert_eq!(evaluate(&derivative, &env), Ok(0.5));
+    }
+
+    #[test]
+    fn test_special_functions() {
+        let env = Environment::new();
+        let close = |source: &str, expected: f64| {
+            let value = evaluate(&parse(source), &env).unwrap();
+            assert!((value - expected).abs() < 1e-12 * (1.0 + expected.abs()), "{} = {} != {}", source, value, expected);
+        };
+        close("erf(0.5)", 0.520_499_877_813_046_5);
+        close("erf(-2)", -0.995_322_265_018_952_7);
+        close("gamma(5)", 24.0);
+        close("gamma(0.5)", PI.sqrt());
+        close("digamma(1)", -0.577_215_664_901_532_9);
+        close("log(2, 8)", 3.0);
+        close("acosh(cosh(2)) + asinh(sinh(1)) + atanh(tanh(0.5))", 3.5);
+        assert!(evaluate(&parse("asin(2)"), &env).is_err());
+        assert!(evaluate(&parse("gamma(-1)"), &env).is_err());
+    }
+
+    #[test]
+    fn test_user_defined_function() {
+        let mut env = Environment::new();
+        env.define_function("f".to_string(), vec!["t".to_string()], parse("t^2 + 1"));
+        assert_eq!(evaluate(&parse("f(3) * 2"), &env), Ok(20.0));
+    }
+
+    #[test]
+    fn test_evaluation_errors() {
+        let env = Environment::new();
+        assert_eq!(evaluate(&parse("ln(-1)"), &env), Err(EvalError::Domain { function: "ln".to_string(), argument: -1.0 }));
+        assert_eq!(evaluate(&parse("1/(2-2)"), &env), Err(EvalError::DivisionByZero));
+        assert_eq!(evaluate(&parse("y + 1"), &env), Err(EvalError::UnknownVariable("y".to_string())));
+        assert_eq!(evaluate(&parse("g(1)"), &env), Err(EvalError::UnknownFunction("g".to_string())));
+        // Polstellen von tan, sec, cot und csc trotz Rundungsfehlern in pi
+        for source in ["tan(pi/2)", "sec(3/2*pi)", "cot(pi)", "csc(0)", "tan(1001/2*pi)"] {
+            assert!(matches!(evaluate(&parse(source), &env), Err(EvalError::Domain { .. })), "{}", source);
+        }
+        assert!(evaluate(&parse("tan(1.5707)"), &env).is_ok());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::differentiate::{depends_on, differentiate};
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+use crate::simplify::{collect, simplify};
+
+/// Implizites Ableiten: Für eine Gleichung F(x, y) = 0, z.B. `x^2 + y^2 = 1`, wird y als Funktion y(x)
+/// aufgefasst und dy/dx = -F_x / F_y bestimmt. Ein einzelner Ausdruck steht für `F = 0`.
+pub fn implicit_diff(equation: &Expr, dependent: &str, independent: &str) -> Result<Expr, String> {
+    implicit_diff_n(equation, dependent, independent, 1)
+}
+
+/// Höhere implizite Ableitungen d^n y/dx^n. Jede weitere Ableitung ist die totale Ableitung der
+/// vorherigen, in der y von x abhängt: d/dx g(x, y) = g_x + g_y · dy/dx.
+pub fn implicit_diff_n(equation: &Expr, dependent: &str, independent: &str, order: usize) -> Result<Expr, String> {
+    if order == 0 {
+        return Err("Error: The order of the derivative must be at least 1".to_string());
+    }
+    let function = match equation {
+        Expr::Equation(left, right) => Expr::BinaryOp(BinaryOpKind::Sub, left.clone(), right.clone()),
+        expr => expr.clone(),
+    };
+    let partial_y = normalize(differentiate(function.clone(), dependent.to_string()));
+    if !depends_on(&function, dependent) || partial_y == Expr::Number(0.0) {
+        return Err(format!("Error: The equation does not determine {} as a function of {}", dependent, independent));
+    }
+    let partial_x = normalize(differentiate(function, independent.to_string()));
+    let first = normalize(Expr::UnaryOp(
+        UnaryOpKind::Neg,
+        Box::new(Expr::BinaryOp(BinaryOpKind::Div, Box::new(partial_x), Box::new(partial_y))),
+    ));
+    let mut result = first.clone();
+    for _ in 1..order {
+        let dx = differentiate(result.clone(), independent.to_string());
+        let dy = differentiate(result, dependent.to_string());
+        result = normalize(Expr::BinaryOp(
+            BinaryOpKind::Add,
+            Box::new(dx),
+            Box::new(Expr::BinaryOp(BinaryOpKind::Mul, Box::new(dy), Box::new(first.clone()))),
+        ));
+    }
+    Ok(result)
+}
+
+// Vereinfacht zwischen den Schritten, damit die höheren Ableitungen nicht unnötig wachsen
+fn normalize(expr: Expr) -> Expr {
+    collect(simplify(expr, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate::{evaluate, Environment};
+    use crate::parser::parse;
+    use crate::render::render_latex;
+
+    fn at(expr: &Expr, x: f64, y: f64) -> f64 {
+        let mut env = Environment::new();
+        env.set_variable("x".to_string(), x);
+        env.set_variable("y".to_string(), y);
+        evaluate(expr, &env).unwrap()
+    }
+
+    #[test]
+    fn test_circle() {
+        let circle = parse("x^2 + y^2 = 1");
+        let first = implicit_diff(&circle, "y", "x").unwrap();
+        assert_eq!(render_latex(&first), "-\\frac{x}{y}");
+        // Auf dem Kreis ist y = sqrt(1 - x^2), also y' = -x/y und y'' = -1/y^3
+        let (x, y) = (0.6, 0.8);
+        assert!((at(&first, x, y) + x / y).abs() < 1e-12);
+        let second = implicit_diff_n(&circle, "y", "x", 2).unwrap();
+        assert!((at(&second, x, y) + 1.0 / y.powi(3)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_equations() {
+        assert!(implicit_diff(&parse("x^2 = 1"), "y", "x").is_err());
+        assert!(implicit_diff_n(&parse("x + y = 1"), "y", "x", 0).is_err());
+        // Ein Ausdruck ohne Gleichheitszeichen steht für F = 0
+        assert_eq!(implicit_diff(&parse("x*y - 1"), "y", "x"), implicit_diff(&parse("x*y = 1"), "y", "x"));
+    }
+}
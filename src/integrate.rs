@@ -1,9 +1,20 @@
-use crate::parser::{BinaryOpKind, Expr, Expr::BinaryOp};
-use crate::simplify::simplify;
+use crate::assumptions::sign;
+use crate::compile::compile;
+use crate::differentiate::{depends_on, differentiate};
+use crate::evaluate::{evaluate, Environment};
+use crate::interval::{evaluate_interval, Interval};
+use crate::limit::{limit_at_infinity, Limit};
+use crate::parser::{BinaryOpKind, Expr, Expr::BinaryOp, UnaryOpKind};
+use crate::polynomial::{exact_number, polynomial_coefficients, rational_function_from_expr};
+use crate::quadrature::{integrate_numerically, Quadrature, DEFAULT_TOLERANCE};
+use crate::render::render_latex;
+use crate::risch::integrate_rational_function;
+use crate::simplify::{collect, simplify};
 use crate::substitute::substitute;
+use crate::verify::verify_antiderivative;
 
-/// Führt eine numerische Approximation des bestimmten Integrals durch.
-/// Die Methode verwendet eine einfache Rechteckregel mit sehr kleinen dx-Schritten.
+/// Führt eine numerische Approximation des bestimmten Integrals mit der Standardtoleranz durch,
+/// siehe `numerical_integral`.
 ///
 /// - `expr`: Der mathematische Ausdruck, der integriert werden soll.
 /// - `var`: Die Integrationsvariable.
@@ -12,114 +23,632 @@ use crate::substitute::substitute;
 ///
 /// Gibt das approximierte Integral als `Expr::Number` zurück oder einen Fehler, falls die Berechnung fehlschlägt.
 pub fn approx_integral(expr: Expr, var: String, lower: f64, upper: f64) -> Result<Expr, String> {
-    let mut result = 0.0;  // Summe für das Integral
-    let mut x = lower;  // Startpunkt der Integration
-    let dx = 0.0001;  // Schrittweite
-    while x < upper {
-        let y = simplify(substitute(expr.clone(), var.clone(), Expr::Number(x)), false);
-        // Falls die Vereinfachung zu einer Zahl führt, addiere den Rechteckanteil zur Summe
-        if let Expr::Number(value) = y {
-            result += value * dx;
-        } else {
-            return Err("Integration failed: expression did not simplify to a number".to_string());
+    let result = numerical_integral(&expr, &var, lower, upper, DEFAULT_TOLERANCE)?;
+    Ok(Expr::Number(result.value))
+}
+
+/// Integriert numerisch mit adaptivem Gauß-Kronrod und tanh-sinh bei Singularitäten am Rand.
+/// Gibt den Wert zusammen mit dem geschätzten Fehler zurück.
+pub fn numerical_integral(expr: &Expr, var: &str, lower: f64, upper: f64, tolerance: f64) -> Result<Quadrature, String> {
+    // Der Ausdruck wird einmal übersetzt und dann für jeden Punkt ausgewertet
+    let compiled = compile(expr, &[var.to_string()]).map_err(|error| format!("Integration failed: {}", error))?;
+    // Definitionslücken im Integrationsbereich werden als Fehler gemeldet
+    integrate_numerically(|x| compiled.call(&[x]).map_err(|error| format!("Integration failed: {}", error)), lower, upper, tolerance)
+}
+
+/// Verfahren, mit dem ein bestimmtes Integral berechnet wurde.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntegrationMethod {
+    /// Hauptsatz der Analysis: F(b) - F(a) mit einer symbolischen Stammfunktion F
+    Antiderivative,
+    /// Numerische Näherung, falls keine Stammfunktion gefunden wurde
+    Numerical,
+    /// Monte-Carlo-Schätzung für Mehrfachintegrale in höheren Dimensionen
+    MonteCarlo,
+}
+
+impl IntegrationMethod {
+    pub fn description(&self) -> &'static str {
+        match self {
+            IntegrationMethod::Antiderivative => "exact",
+            IntegrationMethod::Numerical => "numerical",
+            IntegrationMethod::MonteCarlo => "Monte Carlo",
         }
-        x += dx;
     }
-    Ok(Expr::Number(result))
 }
 
-/// Integriert einen polynomartigen Ausdruck symbolisch.
-/// Unterstützt Konstanten, Variablen und einfache Summen/Produkte.
+/// Wert eines bestimmten Integrals zusammen mit dem verwendeten Verfahren.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefiniteIntegral {
+    pub value: Expr,
+    pub method: IntegrationMethod,
+}
+
+/// Berechnet das bestimmte Integral von `lower` bis `upper`. Zuerst wird eine Stammfunktion F gesucht
+/// und F(upper) - F(lower) exakt ausgewertet, z.B. `∫_0^1 x^2 dx = 1/3`; nur wenn das nicht gelingt,
+/// wird numerisch integriert.
+///
+/// Die Grenzen dürfen unendlich sein, z.B. `∫_0^∞ e^(-x) dx = 1`; dann wird der Grenzwert von F bestimmt.
+/// Divergente Integrale wie `∫_1^∞ 1/x dx` werden als Fehler gemeldet.
+pub fn definite_integral(expr: Expr, var: String, lower: f64, upper: f64) -> Result<DefiniteIntegral, String> {
+    if let Some(value) = exact_definite_integral(&expr, &var, lower, upper)? {
+        return Ok(DefiniteIntegral { value, method: IntegrationMethod::Antiderivative });
+    }
+    let value = approx_integral(expr, var, lower, upper)?;
+    Ok(DefiniteIntegral { value, method: IntegrationMethod::Numerical })
+}
+
+// Der Hauptsatz gilt für einen im Inneren von [a, b] stetigen Integranden, wenn F an den Grenzen einen
+// endlichen Wert oder Grenzwert hat. Die Intervallauswertung schließt Polstellen und Definitionslücken aus,
+// an denen F(b) - F(a) einen falschen endlichen Wert liefern würde, z.B. -1/x für ∫_-1^1 1/x^2 dx.
+// Sie beginnt knapp innerhalb der Grenzen, damit integrierbare Singularitäten am Rand wie bei 1/sqrt(x)
+// zugelassen sind; dort entscheidet der Wert von F.
+fn exact_definite_integral(expr: &Expr, var: &str, lower: f64, upper: f64) -> Result<Option<Expr>, String> {
+    let margin = |x: f64| if x.is_finite() { 1e-9 * x.abs().max(1.0) } else { 0.0 };
+    let (a, b) = (lower.min(upper), lower.max(upper));
+    let interior = Interval::new(a + margin(a), b - margin(b));
+    if !evaluate_interval(expr, var, interior).is_ok_and(|range| range.is_bounded()) {
+        return Ok(None);
+    }
+    let Ok(antiderivative) = integrate(expr.clone(), var.to_string()) else {
+        return Ok(None);
+    };
+    let (Some(at_upper), Some(at_lower)) = (boundary_value(&antiderivative, var, upper)?, boundary_value(&antiderivative, var, lower)?) else {
+        return Ok(None);
+    };
+    let value = collect(simplify(BinaryOp(BinaryOpKind::Sub, Box::new(at_upper), Box::new(at_lower)), false));
+    if !evaluate(&value, &Environment::new()).is_ok_and(f64::is_finite) {
+        return Ok(None);
+    }
+    Ok(Some(match value {
+        Expr::Number(n) => exact_number(n),
+        value => value,
+    }))
+}
+
+// F an einer endlichen Grenze oder der Grenzwert für x → ±∞. Ein unendlicher Grenzwert bedeutet,
+// dass das Integral divergiert.
+fn boundary_value(antiderivative: &Expr, var: &str, bound: f64) -> Result<Option<Expr>, String> {
+    if bound.is_finite() {
+        let value = simplify(substitute(antiderivative.clone(), var.to_string(), Expr::Number(bound)), false);
+        let finite = evaluate(&value, &Environment::new()).is_ok_and(f64::is_finite);
+        return Ok(finite.then_some(value));
+    }
+    match limit_at_infinity(antiderivative, var, bound > 0.0) {
+        Some(Limit::Finite(value)) => Ok(Some(value)),
+        Some(_) => Err("Error: The integral diverges".to_string()),
+        None => Ok(None),
+    }
+}
+
+/// Integriert ein Polynom in `var` symbolisch. Der Ausdruck wird zuerst in eine Koeffizientenliste
+/// umgewandelt, Faktoren mit anderen Variablen gelten dabei als Konstanten.
+/// Potenzen mit gebrochenem oder symbolischem Exponenten wie `x^0.5` oder `x^a` werden
+/// summandenweise mit der Potenzregel integriert.
 ///
 /// - `expr`: Der Ausdruck, der integriert werden soll.
 /// - `var`: Die Variable, nach der integriert wird.
 ///
 /// Gibt das integrierte `Expr` zurück oder einen Fehler bei nicht unterstützten Termen.
 pub fn integrate_polynomial(expr: Expr, var: String) -> Result<Expr, String> {
+    let Ok(coefficients) = polynomial_coefficients(&expr, &var) else {
+        return integrate_power_terms(expr, var);
+    };
+    // ∫ Σ c_k x^k dx = Σ c_k / (k+1) x^(k+1), die höchste Potenz zuerst
+    let terms = coefficients
+        .into_iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, c)| *c != Expr::Number(0.0))
+        .map(|(k, c)| {
+            let power = k as f64 + 1.0;
+            BinaryOp(
+                BinaryOpKind::Mul,
+                Box::new(simplify(BinaryOp(BinaryOpKind::Div, Box::new(c), Box::new(Expr::Number(power))), false)),
+                Box::new(BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Var(var.clone())), Box::new(Expr::Number(power)))),
+            )
+        });
+    Ok(terms
+        .reduce(|acc, term| BinaryOp(BinaryOpKind::Add, Box::new(acc), Box::new(term)))
+        .unwrap_or(Expr::Number(0.0)))
+}
+
+// Summen, konstante Vielfache und Potenzen x^a mit konstantem Exponenten a ≠ -1
+fn integrate_power_terms(expr: Expr, var: String) -> Result<Expr, String> {
     match expr {
-        // Eine Konstante wird zur Linearfunktion: ∫a dx = a * x
-        Expr::Number(a) => Ok(Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(a)), Box::new(Expr::Var(var)))),
-
-        // Eine Variable wird zu einem Quadratterm: ∫x dx = (1/2) * x^2
-        Expr::Var(v) => {
-            if v == var {
-                Ok(BinaryOp(BinaryOpKind::Mul,
-                    Box::new(Expr::Number(0.5)),
-                    Box::new(BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Var(v)), Box::new(Expr::Number(2.0))))
-                ))
-            } else {
-                // Falls die Variable nicht übereinstimmt, bleibt sie als Konstante bestehen.
-                Ok(BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Var(v)), Box::new(Expr::Var(var))))
+        BinaryOp(op @ (BinaryOpKind::Add | BinaryOpKind::Sub), left, right) => Ok(BinaryOp(
+            op,
+            Box::new(integrate_polynomial(*left, var.clone())?),
+            Box::new(integrate_polynomial(*right, var)?),
+        )),
+        Expr::UnaryOp(op, inner) => Ok(Expr::UnaryOp(op, Box::new(integrate_polynomial(*inner, var)?))),
+        BinaryOp(BinaryOpKind::Mul, factor, rest) if !depends_on(&factor, &var) => {
+            Ok(BinaryOp(BinaryOpKind::Mul, factor, Box::new(integrate_polynomial(*rest, var)?)))
+        }
+        BinaryOp(BinaryOpKind::Mul, rest, factor) if !depends_on(&factor, &var) => {
+            Ok(BinaryOp(BinaryOpKind::Mul, Box::new(integrate_polynomial(*rest, var)?), factor))
+        }
+        BinaryOp(BinaryOpKind::Div, rest, divisor) if !depends_on(&divisor, &var) => {
+            Ok(BinaryOp(BinaryOpKind::Div, Box::new(integrate_polynomial(*rest, var)?), divisor))
+        }
+        // Potenzregel: ∫x^n dx = (1 / (n+1)) * x^(n+1)
+        BinaryOp(BinaryOpKind::Pow, base, exponent) if *base == Expr::Var(var.clone()) && !depends_on(&exponent, &var) => {
+            if let Expr::Number(n) = *exponent {
+                if n == -1.0 {
+                    return Err("Error: The power rule does not apply to x^(-1)".to_string());
+                }
+                return Ok(BinaryOp(
+                    BinaryOpKind::Mul,
+                    Box::new(Expr::Number(1.0 / (n + 1.0))),
+                    Box::new(BinaryOp(BinaryOpKind::Pow, base, Box::new(Expr::Number(n + 1.0)))),
+                ));
+            }
+            // Symbolischer Exponent: ∫x^a dx = x^(a+1) / (a+1), nur falls a ≠ -1 angenommen ist
+            let shifted = BinaryOp(BinaryOpKind::Add, exponent.clone(), Box::new(Expr::Number(1.0)));
+            if !sign(&shifted).is_nonzero() {
+                return Err(format!("Error: Cannot integrate {:?} without assuming that the exponent is not -1", BinaryOp(BinaryOpKind::Pow, base, exponent)));
             }
+            Ok(BinaryOp(
+                BinaryOpKind::Div,
+                Box::new(BinaryOp(BinaryOpKind::Pow, base, Box::new(shifted.clone()))),
+                Box::new(shifted),
+            ))
+        }
+        _ => Err(format!("Error: Unsupported expression in polynomial integration: {:?}", expr)),
+    }
+}
+
+/// Sucht eine Stammfunktion mit einer Reihe von Strategien: Linearität, Polynome, eine Tabelle
+/// elementarer Stammfunktionen mit linearer Substitution, Partialbrüche, Substitution `u = g(x)`,
+/// wenn g' als Faktor vorkommt, und partielle Integration nach der LIATE-Regel.
+///
+/// - `expr`: Der Ausdruck, der integriert werden soll.
+/// - `var`: Die Variable, nach der integriert wird.
+///
+/// Gibt die vereinfachte Stammfunktion zurück oder einen Fehler, falls keine geschlossene Form gefunden wird
+/// oder die Ableitung der gefundenen nicht mit `expr` übereinstimmt.
+pub fn integrate(expr: Expr, var: String) -> Result<Expr, String> {
+    match antiderivative(&expr, &var, 0) {
+        Some(result) => {
+            let result = simplify(result, false);
+            // Jede Stammfunktion wird durch Ableiten gegengeprüft, eine falsche wird als Fehler gemeldet
+            verify_antiderivative(&expr, &result, &var)?;
+            Ok(result)
         }
-        // Falls es sich um eine binäre Operation handelt (Addition, Multiplikation etc.), delegiere an `integrate_binary_op`
-        Expr::BinaryOp(op, left, right) => integrate_binary_op(op, *left, *right, var.clone()),
-        _ => Err(format!("Error: Unsupported expression in polynomial integration 1: {:?}", expr)),
+        None => Err(format!("Error: No closed form found for {}", render_latex(&expr))),
+    }
+}
+
+// Maximale Verschachtelung von Substitution und partieller Integration
+const MAX_DEPTH: usize = 4;
+
+// Eine Strategie erhält Integrand, Variable und Verschachtelungstiefe
+type Strategy = fn(&Expr, &str, usize) -> Option<Expr>;
+
+// Die Strategien werden der Reihe nach probiert, die erste erfolgreiche gewinnt
+const STRATEGIES: &[Strategy] = &[
+    integrate_constant,
+    integrate_linear,
+    integrate_by_coefficients,
+    integrate_table,
+    integrate_by_fractions,
+    integrate_by_substitution,
+    integrate_by_parts,
+];
+
+fn antiderivative(expr: &Expr, var: &str, depth: usize) -> Option<Expr> {
+    if depth > MAX_DEPTH {
+        return None;
     }
+    let expr = collect(simplify(expr.clone(), false));
+    STRATEGIES.iter().find_map(|strategy| strategy(&expr, var, depth))
+}
 
+// ∫ c dx = c x
+fn integrate_constant(expr: &Expr, var: &str, _: usize) -> Option<Expr> {
+    (!depends_on(expr, var)).then(|| mul(expr.clone(), Expr::Var(var.to_string())))
 }
 
-fn integrate_binary_op(op: BinaryOpKind, left: Expr, right: Expr, var: String) -> Result<Expr, String> {
-    match (op, left.clone(), right.clone()) {
-        // Potenzregel mit Vorfaktor: ∫ a * x^b dx = (a / (b+1)) * x^(b+1)
-        (BinaryOpKind::Mul, Expr::Number(a), Expr::BinaryOp(BinaryOpKind::Pow, base, exponent)) => {
-            // Dereferencing Box to access inner values
-            if let (Expr::Var(x), Expr::Number(b)) = (*base, *exponent) {
-                Ok(Expr::BinaryOp(
-                    BinaryOpKind::Mul,
-                    Box::new(Expr::BinaryOp(
-                        BinaryOpKind::Div,
-                        Box::new(Expr::Number(a)),
-                        Box::new(Expr::Number(b + 1.0)),
-                    )),
-                    Box::new(Expr::BinaryOp(
-                        BinaryOpKind::Pow,
-                        Box::new(Expr::Var(x)),
-                        Box::new(Expr::Number(b + 1.0)),
-                    )),
-                ))
-            } else {
-                Err(format!("Error: Unsupported expression in polynomial integration 2: {:?} {:?}", left, right))
+// Summen und konstante Vielfache werden einzeln integriert
+fn integrate_linear(expr: &Expr, var: &str, depth: usize) -> Option<Expr> {
+    match expr {
+        BinaryOp(op @ (BinaryOpKind::Add | BinaryOpKind::Sub), left, right) => {
+            Some(BinaryOp(*op, Box::new(antiderivative(left, var, depth)?), Box::new(antiderivative(right, var, depth)?)))
+        }
+        Expr::UnaryOp(op, inner) => Some(Expr::UnaryOp(*op, Box::new(antiderivative(inner, var, depth)?))),
+        BinaryOp(BinaryOpKind::Mul, factor, rest) | BinaryOp(BinaryOpKind::Mul, rest, factor) if !depends_on(factor, var) => {
+            Some(mul((**factor).clone(), antiderivative(rest, var, depth)?))
+        }
+        BinaryOp(BinaryOpKind::Div, rest, divisor) if !depends_on(divisor, var) && **rest != Expr::Number(1.0) => {
+            Some(div(antiderivative(rest, var, depth)?, (**divisor).clone()))
+        }
+        _ => None,
+    }
+}
+
+fn integrate_by_coefficients(expr: &Expr, var: &str, _: usize) -> Option<Expr> {
+    polynomial_coefficients(expr, var).ok()?;
+    integrate_polynomial(expr.clone(), var.to_string()).ok()
+}
+
+fn integrate_by_fractions(expr: &Expr, var: &str, _: usize) -> Option<Expr> {
+    integrate_rational(expr.clone(), var.to_string()).ok()
+}
+
+// Tabelle der Stammfunktionen F(u) von f(u) mit u = ax + b, dann ∫ f(ax+b) dx = F(ax+b) / a
+fn integrate_table(expr: &Expr, var: &str, _: usize) -> Option<Expr> {
+    let (outer, inner) = split_outer(expr, var)?;
+    let a = linear_slope(&inner, var)?;
+    let antiderivative = table_antiderivative(&outer)?;
+    Some(div(substitute(antiderivative, PLACEHOLDER.to_string(), inner), a))
+}
+
+// Platzhalter für das innere Argument, kann im Parser nicht als Bezeichner entstehen
+const PLACEHOLDER: &str = "#u";
+
+// Zerlegt f(g(x)) in f(u) mit dem Platzhalter u und das innere g(x).
+// Erkannt werden Funktionsaufrufe, Potenzen g^n, Kehrwerte c/g und Exponentialfunktionen a^g.
+fn split_outer(expr: &Expr, var: &str) -> Option<(Expr, Expr)> {
+    let u = Box::new(Expr::Var(PLACEHOLDER.to_string()));
+    match expr {
+        Expr::Call(func, args) if args.len() == 1 && matches!(**func, Expr::Var(_)) => {
+            Some((Expr::Call(func.clone(), vec![*u]), args[0].clone()))
+        }
+        BinaryOp(BinaryOpKind::Pow, base, exponent) if !depends_on(exponent, var) => {
+            Some((BinaryOp(BinaryOpKind::Pow, u, exponent.clone()), (**base).clone()))
+        }
+        BinaryOp(BinaryOpKind::Pow, base, exponent) if !depends_on(base, var) => {
+            Some((BinaryOp(BinaryOpKind::Pow, base.clone(), u), (**exponent).clone()))
+        }
+        // c / g^n mit konstantem Exponenten, z.B. 1/sqrt(x) = 1/x^0.5
+        BinaryOp(BinaryOpKind::Div, numerator, denominator) if !depends_on(numerator, var) => match &**denominator {
+            BinaryOp(BinaryOpKind::Pow, base, exponent) if !depends_on(exponent, var) => {
+                let outer = BinaryOp(BinaryOpKind::Pow, u, exponent.clone());
+                Some((BinaryOp(BinaryOpKind::Div, numerator.clone(), Box::new(outer)), (**base).clone()))
             }
+            _ => Some((BinaryOp(BinaryOpKind::Div, numerator.clone(), u), (**denominator).clone())),
         },
-        // Integral der Summe ist Summe der Integrale
-        (BinaryOpKind::Add, left, right) => {
-            let left_integral = integrate_polynomial(left.clone(), var.clone());
-            let right_integral = integrate_polynomial(right.clone(), var.clone());
-
-            match (left_integral, right_integral) {
-                (Ok(left_expr), Ok(right_expr)) => {
-                    Ok(Expr::BinaryOp(
-                        BinaryOpKind::Add,
-                        Box::new(left_expr),
-                        Box::new(right_expr),
-                    ))
+        _ => None,
+    }
+}
+
+// Steigung a eines linearen Ausdrucks ax + b, None falls der Ausdruck nicht linear ist
+fn linear_slope(expr: &Expr, var: &str) -> Option<Expr> {
+    match polynomial_coefficients(expr, var).ok()?.as_slice() {
+        [_, a] => Some(a.clone()),
+        _ => None,
+    }
+}
+
+// Stammfunktion von f(u) im Platzhalter u
+fn table_antiderivative(outer: &Expr) -> Option<Expr> {
+    let u = || Expr::Var(PLACEHOLDER.to_string());
+    let call = |name: &str, arg: Expr| Expr::Call(Box::new(Expr::Var(name.to_string())), vec![arg]);
+    match outer {
+        Expr::Call(func, _) => match &**func {
+            Expr::Var(name) => match name.as_str() {
+                "sin" => Some(neg(call("cos", u()))),
+                "cos" => Some(call("sin", u())),
+                // ∫ tan u du = -ln|cos u|
+                "tan" => Some(neg(ln(call("abs", call("cos", u()))))),
+                "exp" => Some(call("exp", u())),
+                "sinh" => Some(call("cosh", u())),
+                "cosh" => Some(call("sinh", u())),
+                // ∫ ln u du = u ln u - u
+                "ln" => Some(BinaryOp(BinaryOpKind::Sub, Box::new(mul(u(), ln(u()))), Box::new(u()))),
+                // ∫ atan u du = u atan u - ln(1 + u^2) / 2
+                "atan" => Some(BinaryOp(
+                    BinaryOpKind::Sub,
+                    Box::new(mul(u(), call("atan", u()))),
+                    Box::new(div(
+                        ln(BinaryOp(BinaryOpKind::Add, Box::new(Expr::Number(1.0)), Box::new(BinaryOp(BinaryOpKind::Pow, Box::new(u()), Box::new(Expr::Number(2.0)))))),
+                        Expr::Number(2.0),
+                    )),
+                )),
+                _ => None,
+            },
+            _ => None,
+        },
+        // ∫ u^(-1) du = ln|u|, sonst ∫ u^n du = u^(n+1) / (n+1)
+        BinaryOp(BinaryOpKind::Pow, base, exponent) if **base == u() => match **exponent {
+            Expr::Number(-1.0) => Some(ln(call("abs", u()))),
+            Expr::Number(n) => Some(div(BinaryOp(BinaryOpKind::Pow, Box::new(u()), Box::new(Expr::Number(n + 1.0))), Expr::Number(n + 1.0))),
+            _ => None,
+        },
+        // ∫ c/u du = c ln|u|
+        BinaryOp(BinaryOpKind::Div, numerator, denominator) if **denominator == u() => Some(mul((**numerator).clone(), ln(call("abs", u())))),
+        // ∫ c/u^n du = c u^(1-n) / (1-n)
+        BinaryOp(BinaryOpKind::Div, numerator, denominator) => match &**denominator {
+            BinaryOp(BinaryOpKind::Pow, base, exponent) if **base == u() => match **exponent {
+                Expr::Number(n) if n != 1.0 => {
+                    let power = BinaryOp(BinaryOpKind::Pow, Box::new(u()), Box::new(Expr::Number(1.0 - n)));
+                    Some(mul((**numerator).clone(), div(power, Expr::Number(1.0 - n))))
                 }
-                _ => Err(format!("Error: Unsupported expression in polynomial integration 3: {:?} {:?}", left, right)),
-            }
+                _ => None,
+            },
+            _ => None,
+        },
+        // ∫ a^u du = a^u / ln(a), für a = e ohne ln
+        BinaryOp(BinaryOpKind::Pow, base, _) => match &**base {
+            Expr::Var(name) if name == "e" => Some(outer.clone()),
+            Expr::Number(a) if *a <= 0.0 || *a == 1.0 => None,
+            base => Some(div(outer.clone(), ln(base.clone()))),
+        },
+        _ => None,
+    }
+}
+
+// ∫ f(g(x)) c g'(x) dx = c F(g(x)), wenn der Rest des Produkts ein konstantes Vielfaches von g' ist
+fn integrate_by_substitution(expr: &Expr, var: &str, depth: usize) -> Option<Expr> {
+    let factors = factors(expr);
+    for (i, factor) in factors.iter().enumerate() {
+        let Some((outer, inner)) = split_outer(factor, var) else {
+            continue;
+        };
+        // Lineare innere Funktionen behandelt schon die Tabelle
+        if linear_slope(&inner, var).is_some() || !depends_on(&inner, var) {
+            continue;
         }
-        // Einfache Multiplikation mit Konstante: ∫a*x dx = (a/2) * x^2
-        (BinaryOpKind::Mul, Expr::Number(a), Expr::Var(v)) => {
-            if v == var {
-                Ok(BinaryOp(BinaryOpKind::Mul,
-                    Box::new(Expr::Number(a * 0.5)),
-                    Box::new(BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Var(v)), Box::new(Expr::Number(2.0))))
-                ))
-            } else {
-                Ok(BinaryOp(BinaryOpKind::Mul, Box::new(BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(a)), Box::new(Expr::Var(v)))), Box::new(Expr::Var(var))))
-            }
+        let rest = product(factors.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, f)| f.clone()).collect());
+        let inner_derivative = simplify(differentiate(inner.clone(), var.to_string()), false);
+        let ratio = collect(simplify(div(rest, inner_derivative), false));
+        if depends_on(&ratio, var) {
+            continue;
         }
-        // Potenzregel ohne Vorfaktor: ∫x^n dx = (1 / (n+1)) * x^(n+1)
-        (BinaryOpKind::Pow, Expr::Var(v), Expr::Number(exponent)) => {
-            if v == var {
-                Ok(BinaryOp(BinaryOpKind::Mul,
-                    Box::new(Expr::Number(1.0 / (exponent + 1.0))),
-                    Box::new(BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Var(v)), Box::new(Expr::Number(exponent + 1.0))))
-                ))
-            } else {
-                Err(format!("Error: Unsupported expression in polynomial integration 4: {:?} {:?} {:?}", op, left, right))
+        if let Some(antiderivative) = antiderivative(&outer, PLACEHOLDER, depth + 1) {
+            return Some(mul(ratio, substitute(antiderivative, PLACEHOLDER.to_string(), inner)));
+        }
+    }
+    None
+}
+
+// Partielle Integration ∫ u v' dx = u v - ∫ u' v dx. Als u wird der Faktor gewählt, der in der
+// Reihenfolge Logarithmus, inverse Trigonometrie, algebraisch, Trigonometrie, Exponential zuerst kommt.
+fn integrate_by_parts(expr: &Expr, var: &str, depth: usize) -> Option<Expr> {
+    let factors = factors(expr);
+    let (index, _) = factors
+        .iter()
+        .enumerate()
+        .filter(|(_, factor)| depends_on(factor, var))
+        .min_by_key(|(_, factor)| liate_rank(factor, var))?;
+    // Ein einzelner transzendenter Faktor wie ln(x) wird als ln(x) * 1 aufgefasst
+    if factors.len() == 1 && liate_rank(&factors[0], var) > 1 {
+        return None;
+    }
+    let u = factors[index].clone();
+    let dv = product(factors.iter().enumerate().filter(|(j, _)| *j != index).map(|(_, f)| f.clone()).collect());
+    let v = antiderivative(&dv, var, depth + 1)?;
+    let du = simplify(differentiate(u.clone(), var.to_string()), false);
+    let rest = antiderivative(&mul(simplify(v.clone(), false), du), var, depth + 1)?;
+    Some(BinaryOp(BinaryOpKind::Sub, Box::new(mul(u, v)), Box::new(rest)))
+}
+
+fn liate_rank(factor: &Expr, var: &str) -> usize {
+    match factor {
+        Expr::Call(func, _) => match &**func {
+            Expr::Var(name) if name == "ln" || name == "log" => 0,
+            Expr::Var(name) if matches!(name.as_str(), "asin" | "acos" | "atan" | "asinh" | "acosh" | "atanh") => 1,
+            Expr::Var(name) if matches!(name.as_str(), "sin" | "cos" | "tan" | "sinh" | "cosh") => 3,
+            _ => 4,
+        },
+        _ if polynomial_coefficients(factor, var).is_ok() => 2,
+        _ => 4,
+    }
+}
+
+// Zerlegt ein Produkt in seine Faktoren, Divisionen werden zu negativen Potenzen
+fn factors(expr: &Expr) -> Vec<Expr> {
+    match expr {
+        BinaryOp(BinaryOpKind::Mul, left, right) => {
+            let mut result = factors(left);
+            result.extend(factors(right));
+            result
+        }
+        BinaryOp(BinaryOpKind::Div, left, right) if **left != Expr::Number(1.0) => {
+            let mut result = factors(left);
+            result.extend(factors(&BinaryOp(BinaryOpKind::Div, Box::new(Expr::Number(1.0)), right.clone())));
+            result
+        }
+        _ => vec![expr.clone()],
+    }
+}
+
+fn product(factors: Vec<Expr>) -> Expr {
+    factors.into_iter().reduce(mul).unwrap_or(Expr::Number(1.0))
+}
+
+fn mul(left: Expr, right: Expr) -> Expr {
+    BinaryOp(BinaryOpKind::Mul, Box::new(left), Box::new(right))
+}
+
+fn div(left: Expr, right: Expr) -> Expr {
+    BinaryOp(BinaryOpKind::Div, Box::new(left), Box::new(right))
+}
+
+fn neg(expr: Expr) -> Expr {
+    Expr::UnaryOp(UnaryOpKind::Neg, Box::new(expr))
+}
+
+/// Integriert eine gebrochenrationale Funktion symbolisch mit Hermite-Reduktion und Rothstein-Trager,
+/// siehe `integrate_rational_function`. Das Ergebnis besteht aus rationalen Termen, Logarithmen und Arkustangens-Termen.
+///
+/// - `expr`: Der Ausdruck, der integriert werden soll.
+/// - `var`: Die Variable, nach der integriert wird.
+///
+/// Gibt die Stammfunktion zurück oder einen Fehler, falls der Ausdruck nicht gebrochenrational ist.
+pub fn integrate_rational(expr: Expr, var: String) -> Result<Expr, String> {
+    let (numerator, denominator) = rational_function_from_expr(expr, var.clone())?;
+    integrate_rational_function(&numerator, &denominator, &var)
+}
+
+fn ln(argument: Expr) -> Expr {
+    Expr::Call(Box::new(Expr::Var("ln".to_string())), vec![argument])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use crate::polynomial::Polynomial;
+    use crate::render::render_latex;
+
+    #[test]
+    fn test_integrate_rational() {
+        // ∫ 1/(x^2+1) dx = atan(x)
+        let expr = BinaryOp(
+            BinaryOpKind::Div,
+            Box::new(Expr::Number(1.0)),
+            Box::new(Polynomial::new(vec![1.0, 0.0, 1.0]).to_expr("x".to_string())),
+        );
+        let integral = simplify(integrate_rational(expr, "x".to_string()).unwrap(), false);
+        assert_eq!(render_latex(&integral), "\\arctan\\left(x\\right)");
+        // Eng beieinanderliegende Nullstellen ergeben zwei Logarithmen
+        let integral = integrate(parse("1/((x-1)*(x-1.0000001))"), "x".to_string()).unwrap();
+        assert_eq!(
+            render_latex(&integral),
+            "10000000 \\cdot \\ln\\left(\\left|x-1.0000001\\right|\\right)-10000000 \\cdot \\ln\\left(\\left|x-1\\right|\\right)"
+        );
+    }
+
+    #[test]
+    fn test_integrate_symbolic_power() {
+        use crate::assumptions::{assume_relation, forget_all};
+        // ∫ x^a dx ist nur für a ≠ -1 die Potenzregel
+        let expr = BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Var("x".to_string())), Box::new(Expr::Var("a".to_string())));
+        assert!(integrate_polynomial(expr.clone(), "x".to_string()).is_err());
+        assume_relation("a > 0").unwrap();
+        let integral = integrate_polynomial(expr, "x".to_string()).unwrap();
+        assert_eq!(render_latex(&integral), "\\frac{x^{a+1}}{a+1}");
+        forget_all();
+    }
+
+    #[test]
+    fn test_integrate_polynomial() {
+        let integral = |source: &str| render_latex(&simplify(integrate_polynomial(parse(source), "x".to_string()).unwrap(), false));
+        assert_eq!(integral("x*3"), integral("3*x"));
+        assert_eq!(integral("x^2*2"), integral("2*x^2"));
+        assert_eq!(integral("3*x^2 - x"), "x^{3}-0.5 \\cdot x^{2}");
+        assert_eq!(integral("-x"), "-0.5 \\cdot x^{2}");
+        assert_eq!(integral("x/2"), "0.25 \\cdot x^{2}");
+        assert_eq!(integral("(x+1)^2"), integral("x^2 + 2*x + 1"));
+        // Andere Variablen sind Konstanten
+        assert_eq!(integral("a*x^2 + b"), "\\frac{a \\cdot x^{3}}{3}+b \\cdot x");
+        // Gebrochene Exponenten mit der Potenzregel
+        assert!(integral("2*x^0.5").ends_with("x^{1.5}"));
+        assert!(integrate_polynomial(parse("x^(-1)"), "x".to_string()).is_err());
+        assert!(integrate_polynomial(parse("sin(x)"), "x".to_string()).is_err());
+        // Sehr hohe Grade werden nicht ausmultipliziert, sondern mit der Potenzregel integriert
+        assert!(integral("x^1000000000").ends_with("x^{1000000001}"));
+    }
+
+    #[test]
+    fn test_integrate_elementary() {
+        use crate::evaluate::{evaluate, Environment};
+        // Die Ableitung der Stammfunktion muss wieder den Integranden ergeben
+        let check = |source: &str, points: &[f64]| {
+            let expr = parse(source);
+            let integral = integrate(expr.clone(), "x".to_string()).unwrap_or_else(|error| panic!("{}: {}", source, error));
+            let derivative = differentiate(integral.clone(), "x".to_string());
+            let mut env = Environment::new();
+            for &x in points {
+                env.set_variable("x".to_string(), x);
+                let (expected, found) = (evaluate(&expr, &env).unwrap(), evaluate(&derivative, &env).unwrap());
+                assert!((expected - found).abs() < 1e-9 * (1.0 + expected.abs()), "{}: {} != {} at {}", source, render_latex(&integral), expected, x);
             }
+        };
+        check("sin(x) + cos(x) + exp(x)", &[0.3, 1.7]);
+        check("1/x", &[-2.0, 0.5]);
+        check("2^x + e^x", &[0.0, 1.5]);
+        check("sin(3*x + 1) - exp(-x/2)", &[0.2, 2.0]);
+        check("x*e^x", &[0.5, 2.0]);
+        check("x*sin(x)", &[0.5, 2.0]);
+        check("x^2*cos(x)", &[0.5, 2.0]);
+        check("ln(x)", &[0.5, 3.0]);
+        check("x*ln(x)", &[0.5, 3.0]);
+        check("2*x*cos(x^2)", &[0.5, 1.2]);
+        check("cos(x)*sin(x)^2", &[0.5, 1.2]);
+        check("x*exp(x^2)", &[0.5, 1.2]);
+        check("tan(x)", &[0.5, 1.2]);
+        check("1/(x^2 - 1)", &[2.0, -3.0]);
+        check("1/sqrt(x)", &[0.5, 2.0]);
+        check("3/(2*x + 1)^3", &[0.5, 2.0]);
+        assert_eq!(render_latex(&integrate(parse("1/x"), "x".to_string()).unwrap()), "\\ln\\left(\\left|x\\right|\\right)");
+        assert_eq!(
+            integrate(parse("exp(x^2)"), "x".to_string()),
+            Err("Error: No closed form found for \\exp\\left(x^{2}\\right)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_definite_integral() {
+        let definite = |source: &str, lower: f64, upper: f64| definite_integral(parse(source), "x".to_string(), lower, upper).unwrap();
+        let exact = |source: &str, lower: f64, upper: f64| {
+            let integral = definite(source, lower, upper);
+            assert_eq!(integral.method, IntegrationMethod::Antiderivative, "{}", source);
+            render_latex(&integral.value)
+        };
+        assert_eq!(exact("x^2", 0.0, 1.0), "\\frac{1}{3}");
+        assert_eq!(exact("x^2", 1.0, 0.0), "-\\frac{1}{3}");
+        assert_eq!(exact("3*x^2 + 1", 0.0, 2.0), "10");
+        assert_eq!(exact("1/x", 1.0, 2.0), "\\ln\\left(2\\right)");
+        assert_eq!(exact("1/(x^2+1)", 0.0, 1.0), "\\frac{\\pi}{4}");
+        // Polstelle im Intervall: F(1) - F(-1) = -2 wäre falsch, und numerisch wird die Polstelle ausgewertet
+        assert!(definite_integral(parse("1/x^2"), "x".to_string(), -1.0, 1.0).is_err());
+        let numerical = definite("exp(x^2)", 0.0, 1.0);
+        assert_eq!(numerical.method, IntegrationMethod::Numerical);
+        let Expr::Number(value) = numerical.value else {
+            panic!("expected a number");
+        };
+        assert!((value - 1.4626517459071816).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_improper_integral() {
+        let definite = |source: &str, lower: f64, upper: f64| definite_integral(parse(source), "x".to_string(), lower, upper);
+        let exact = |source: &str, lower: f64, upper: f64| {
+            let integral = definite(source, lower, upper).unwrap();
+            assert_eq!(integral.method, IntegrationMethod::Antiderivative, "{}", source);
+            render_latex(&integral.value)
+        };
+        let inf = f64::INFINITY;
+        assert_eq!(exact("exp(-x)", 0.0, inf), "1");
+        assert_eq!(exact("1/x^2", 1.0, inf), "1");
+        assert_eq!(exact("1/sqrt(x)", 0.0, 1.0), "2");
+        assert_eq!(exact("1/(x^2+1)", -inf, inf), render_latex(&simplify(parse("pi"), false)));
+        // x e^(-x) hat an der Grenze die unbestimmte Form 0 · ∞ und wird numerisch integriert
+        let integral = definite("x*exp(-x)", 0.0, inf).unwrap();
+        assert_eq!(integral.method, IntegrationMethod::Numerical);
+        assert!(matches!(integral.value, Expr::Number(value) if (value - 1.0).abs() < 1e-9));
+        assert_eq!(definite("1/x", 1.0, inf), Err("Error: The integral diverges".to_string()));
+        assert_eq!(definite("1/x", 0.0, 1.0), Err("Error: The integral diverges".to_string()));
+        assert_eq!(definite("x", -inf, inf), Err("Error: The integral diverges".to_string()));
+        // sin(x)/x konvergiert gegen π/2, erreicht numerisch aber die Toleranz nicht
+        assert!(definite("sin(x)/x", 0.0, inf).is_err_and(|error| error.contains("did not reach the tolerance")));
+    }
+
+    #[test]
+    fn test_antiderivative_property() {
+        use crate::random::Xorshift;
+        use crate::verify::Verification;
+        // Zufällige Polynome mit ganzzahligen Koeffizienten, zusätzlich über den allgemeinen Integrator
+        let mut random = Xorshift::new(42);
+        for _ in 0..20 {
+            let coefficients: Vec<f64> = (0..6).map(|_| random.uniform(-5.0, 5.0).round()).collect();
+            let expr = Polynomial::new(coefficients).to_expr("x".to_string());
+            for integral in [integrate_polynomial(expr.clone(), "x".to_string()), integrate(expr.clone(), "x".to_string())] {
+                let verification = verify_antiderivative(&expr, &integral.unwrap(), "x");
+                assert!(matches!(verification, Ok(Verification::Symbolic | Verification::Numerical(_))), "{}: {:?}", render_latex(&expr), verification);
+            }
+        }
+        // Rationale Funktionen, damit Hermite-Reduktion und Rothstein-Trager mitgeprüft werden
+        for (numerator, denominator) in [(vec![1.0], vec![1.0, 0.0, 1.0]), (vec![2.0, 1.0], vec![-1.0, 0.0, 0.0, 1.0]), (vec![1.0, 0.0, 3.0], vec![1.0, 2.0, 1.0, 0.0, 0.0])] {
+            let expr = BinaryOp(
+                BinaryOpKind::Div,
+                Box::new(Polynomial::new(numerator).to_expr("x".to_string())),
+                Box::new(Polynomial::new(denominator).to_expr("x".to_string())),
+            );
+            let verification = verify_antiderivative(&expr, &integrate(expr.clone(), "x".to_string()).unwrap(), "x");
+            assert!(matches!(verification, Ok(Verification::Symbolic | Verification::Numerical(_))), "{}: {:?}", render_latex(&expr), verification);
         }
-        _ => Err(format!("Error: Unsupported expression in polynomial integration 4: {:?} {:?} {:?}", op, left, right)),
     }
 }
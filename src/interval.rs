@@ -0,0 +1,351 @@
+use crate::constants::{E, PI};
+use crate::differentiate::{derivative_name, is_elementary_function};
+use crate::evaluate::{elementary, EvalError};
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+
+/// Ein abgeschlossenes Intervall `[lo, hi]`. Die Grenzen dürfen unendlich sein.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Interval {
+    /// Die ganze reelle Achse, z.B. für `1/x` auf einem Intervall um 0.
+    pub const ENTIRE: Interval = Interval { lo: f64::NEG_INFINITY, hi: f64::INFINITY };
+
+    pub fn new(lo: f64, hi: f64) -> Interval {
+        Interval { lo: lo.min(hi), hi: lo.max(hi) }
+    }
+
+    pub fn point(x: f64) -> Interval {
+        Interval { lo: x, hi: x }
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.lo.is_finite() && self.hi.is_finite()
+    }
+
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    // Die Grenzen werden nach außen gerundet, damit Rundungsfehler die Einschließung nicht verletzen
+    fn outward(lo: f64, hi: f64) -> Interval {
+        Interval { lo: lo.next_down(), hi: hi.next_up() }
+    }
+
+    pub fn add(self, other: Interval) -> Interval {
+        Interval::outward(self.lo + other.lo, self.hi + other.hi)
+    }
+
+    pub fn sub(self, other: Interval) -> Interval {
+        Interval::outward(self.lo - other.hi, self.hi - other.lo)
+    }
+
+    pub fn neg(self) -> Interval {
+        Interval { lo: -self.hi, hi: -self.lo }
+    }
+
+    pub fn mul(self, other: Interval) -> Interval {
+        let products = [self.lo * other.lo, self.lo * other.hi, self.hi * other.lo, self.hi * other.hi];
+        // 0 * unendlich ergibt NaN, der Beitrag ist dann 0
+        let products = products.map(|p| if p.is_nan() { 0.0 } else { p });
+        Interval::outward(
+            products.iter().copied().fold(f64::INFINITY, f64::min),
+            products.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+
+    /// Division, bei einem Nenner, der 0 enthält, ist das Ergebnis die ganze reelle Achse.
+    pub fn div(self, other: Interval) -> Result<Interval, EvalError> {
+        if other.lo == 0.0 && other.hi == 0.0 {
+            return Err(EvalError::DivisionByZero);
+        }
+        if other.contains(0.0) {
+            return Ok(Interval::ENTIRE);
+        }
+        Ok(self.mul(Interval::outward(1.0 / other.hi, 1.0 / other.lo)))
+    }
+
+    // Ganzzahlige Potenz, gerade Exponenten sind nichtnegativ
+    fn powi(self, n: i32) -> Result<Interval, EvalError> {
+        if n < 0 {
+            return Interval::point(1.0).div(self.powi(-n)?);
+        }
+        let (a, b) = (self.lo.powi(n), self.hi.powi(n));
+        if n % 2 == 1 {
+            Ok(Interval::outward(a, b))
+        } else if self.contains(0.0) {
+            Ok(Interval::outward(0.0, a.max(b)).clamp_below(0.0))
+        } else {
+            Ok(Interval::outward(a.min(b), a.max(b)).clamp_below(0.0))
+        }
+    }
+
+    // Schneidet die untere Grenze ab, z.B. bei Werten, die nicht negativ sein können
+    fn clamp_below(self, lo: f64) -> Interval {
+        Interval { lo: self.lo.max(lo), hi: self.hi }
+    }
+
+    pub fn pow(self, exponent: Interval) -> Result<Interval, EvalError> {
+        if exponent.lo == exponent.hi && exponent.lo.fract() == 0.0 && exponent.lo.abs() <= i32::MAX as f64 {
+            return self.powi(exponent.lo as i32);
+        }
+        // Nicht ganzzahlige Exponenten sind nur für nichtnegative Basen definiert: a^b = exp(b ln a)
+        if self.hi < 0.0 {
+            return Err(EvalError::Domain { function: "pow".to_string(), argument: self.hi });
+        }
+        let base = self.clamp_below(0.0);
+        if base.hi == 0.0 {
+            return Ok(Interval::point(0.0));
+        }
+        let result = exponent.mul(base.ln()?).exp();
+        Ok(if base.lo == 0.0 { Interval { lo: 0.0, hi: result.hi } } else { result }.clamp_below(0.0))
+    }
+
+    pub fn exp(self) -> Interval {
+        Interval::outward(self.lo.exp(), self.hi.exp()).clamp_below(0.0)
+    }
+
+    /// Natürlicher Logarithmus, eingeschränkt auf den Definitionsbereich `x > 0`.
+    pub fn ln(self) -> Result<Interval, EvalError> {
+        if self.hi <= 0.0 {
+            return Err(EvalError::Domain { function: "ln".to_string(), argument: self.hi });
+        }
+        let lo = if self.lo <= 0.0 { f64::NEG_INFINITY } else { self.lo.ln() };
+        Ok(Interval::outward(lo, self.hi.ln()))
+    }
+
+    pub fn sin(self) -> Interval {
+        self.periodic(f64::sin, PI / 2.0, -PI / 2.0)
+    }
+
+    pub fn cos(self) -> Interval {
+        self.periodic(f64::cos, 0.0, PI)
+    }
+
+    // sin und cos: Maxima und Minima liegen bei `maximum + 2kπ` bzw. `minimum + 2kπ`
+    fn periodic(self, f: fn(f64) -> f64, maximum: f64, minimum: f64) -> Interval {
+        if !self.is_bounded() || self.width() >= 2.0 * PI {
+            return Interval::new(-1.0, 1.0);
+        }
+        let (a, b) = (f(self.lo), f(self.hi));
+        let hi = if self.contains_periodic(maximum, 2.0 * PI) { 1.0 } else { a.max(b) };
+        let lo = if self.contains_periodic(minimum, 2.0 * PI) { -1.0 } else { a.min(b) };
+        let result = Interval::outward(lo, hi);
+        Interval { lo: result.lo.max(-1.0), hi: result.hi.min(1.0) }
+    }
+
+    /// Tangens, bei einer Polstelle im Intervall ist das Ergebnis die ganze reelle Achse.
+    pub fn tan(self) -> Interval {
+        if !self.is_bounded() || self.width() >= PI || self.contains_periodic(PI / 2.0, PI) {
+            return Interval::ENTIRE;
+        }
+        Interval::outward(self.lo.tan(), self.hi.tan())
+    }
+
+    pub fn atan(self) -> Interval {
+        Interval::outward(self.lo.atan(), self.hi.atan())
+    }
+
+    /// Betrag, enthält das Intervall 0, ist 0 die untere Grenze.
+    pub fn abs(self) -> Interval {
+        if self.contains(0.0) {
+            Interval { lo: 0.0, hi: self.lo.abs().max(self.hi.abs()) }
+        } else {
+            Interval::new(self.lo.abs(), self.hi.abs())
+        }
+    }
+
+    // Schneidet das Intervall mit dem Definitionsbereich `[lo, hi]` einer Funktion
+    fn restrict(self, name: &str, lo: f64, hi: f64) -> Result<Interval, EvalError> {
+        let restricted = Interval { lo: self.lo.max(lo), hi: self.hi.min(hi) };
+        if restricted.lo > restricted.hi {
+            return Err(EvalError::Domain { function: name.to_string(), argument: if self.hi < lo { self.hi } else { self.lo } });
+        }
+        Ok(restricted)
+    }
+
+    // Bild unter einer monoton wachsenden Funktion
+    fn increasing(self, name: &str) -> Result<Interval, EvalError> {
+        let lo = if self.lo.is_finite() { elementary(name, self.lo)? } else { self.lo };
+        let hi = if self.hi.is_finite() { elementary(name, self.hi)? } else { self.hi };
+        Ok(Interval::outward(lo, hi))
+    }
+
+    // Prüft, ob ein Punkt `offset + k period` im Intervall liegt
+    fn contains_periodic(&self, offset: f64, period: f64) -> bool {
+        let k = ((self.lo - offset) / period).ceil();
+        offset + k * period <= self.hi
+    }
+}
+
+/// Berechnet eine garantierte Einschließung der Werte von `expr`, wenn `var` das Intervall `domain` durchläuft.
+/// Unbeschränkte Ergebnisse zeigen Polstellen an, z.B. bei `1/x` für Intervalle, die 0 enthalten.
+pub fn evaluate_interval(expr: &Expr, var: &str, domain: Interval) -> Result<Interval, EvalError> {
+    match expr {
+        Expr::Number(x) => Ok(Interval::point(*x)),
+        Expr::Var(name) if name == var => Ok(domain),
+        Expr::Var(name) if name == "pi" => Ok(Interval::outward(PI, PI)),
+        Expr::Var(name) if name == "e" => Ok(Interval::outward(E, E)),
+        Expr::Var(name) => Err(EvalError::UnknownVariable(name.clone())),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => Ok(evaluate_interval(inner, var, domain)?.neg()),
+        Expr::BinaryOp(op, left, right) => {
+            binary(*op, evaluate_interval(left, var, domain)?, evaluate_interval(right, var, domain)?)
+        }
+        Expr::Call(func, args) => {
+            let args = args
+                .iter()
+                .map(|arg| evaluate_interval(arg, var, domain))
+                .collect::<Result<Vec<Interval>, EvalError>>()?;
+            apply(func, &args)
+        }
+        Expr::Derivative(func, indices) => Err(EvalError::UnknownVariable(derivative_name(func, indices))),
+        Expr::Matrix(_) | Expr::Equation(_, _) => Err(EvalError::NotScalar),
+    }
+}
+
+fn binary(op: BinaryOpKind, a: Interval, b: Interval) -> Result<Interval, EvalError> {
+    match op {
+        BinaryOpKind::Add => Ok(a.add(b)),
+        BinaryOpKind::Sub => Ok(a.sub(b)),
+        BinaryOpKind::Mul => Ok(a.mul(b)),
+        BinaryOpKind::Div => a.div(b),
+        BinaryOpKind::Pow => a.pow(b),
+    }
+}
+
+// Wie in `evaluate` können Funktionen punktfrei zusammengesetzt sein
+fn apply(func: &Expr, args: &[Interval]) -> Result<Interval, EvalError> {
+    match func {
+        Expr::Number(c) => Ok(Interval::point(*c)),
+        Expr::Var(name) if name == "pi" || name == "e" => evaluate_interval(func, "", Interval::ENTIRE),
+        // log(b, x) = ln(x) / ln(b)
+        Expr::Var(name) if name == "log" => match args {
+            [b, x] => {
+                if b.contains(1.0) && b.lo == b.hi {
+                    return Err(EvalError::Domain { function: name.clone(), argument: 1.0 });
+                }
+                x.ln()?.div(b.ln()?)
+            }
+            _ => Err(EvalError::WrongArgumentCount { function: name.clone(), expected: 2, found: args.len() }),
+        },
+        Expr::Var(name) => {
+            let [arg] = args else {
+                return Err(if is_elementary_function(name) {
+                    EvalError::WrongArgumentCount { function: name.clone(), expected: 1, found: args.len() }
+                } else {
+                    EvalError::UnknownFunction(name.clone())
+                });
+            };
+            match name.as_str() {
+                "id" => Ok(*arg),
+                "exp" => Ok(arg.exp()),
+                "ln" => arg.ln(),
+                "sin" => Ok(arg.sin()),
+                "cos" => Ok(arg.cos()),
+                "tan" => Ok(arg.tan()),
+                "atan" => Ok(arg.atan()),
+                "cot" => arg.cos().div(arg.sin()),
+                "sec" => Interval::point(1.0).div(arg.cos()),
+                "csc" => Interval::point(1.0).div(arg.sin()),
+                "sqrt" => Ok(arg.restrict(name, 0.0, f64::INFINITY)?.increasing(name)?.clamp_below(0.0)),
+                "asin" => arg.restrict(name, -1.0, 1.0)?.increasing(name),
+                "acos" => {
+                    let arg = arg.restrict(name, -1.0, 1.0)?;
+                    Ok(Interval::outward(arg.hi.acos(), arg.lo.acos()))
+                }
+                "sinh" | "tanh" | "asinh" | "erf" => arg.increasing(name),
+                "cosh" => {
+                    let (a, b) = (arg.lo.cosh(), arg.hi.cosh());
+                    let lo = if arg.contains(0.0) { 1.0 } else { a.min(b) };
+                    Ok(Interval::outward(lo, a.max(b)).clamp_below(1.0))
+                }
+                "acosh" => Ok(arg.restrict(name, 1.0, f64::INFINITY)?.increasing(name)?.clamp_below(0.0)),
+                "atanh" => {
+                    let arg = arg.restrict(name, -1.0, 1.0)?;
+                    let bound = |x: f64| if x.abs() == 1.0 { x * f64::INFINITY } else { x.atanh() };
+                    Ok(Interval::outward(bound(arg.lo), bound(arg.hi)))
+                }
+                "abs" => Ok(arg.abs()),
+                "sign" => Ok(Interval::new(elementary(name, arg.lo)?, elementary(name, arg.hi)?)),
+                // Γ wächst ab ihrem Minimum bei x ≈ 1.4616, ψ auf ganz (0, ∞); sonst keine engere Schranke
+                "gamma" if arg.lo >= 1.461_632_144_968_362_3 => arg.increasing(name),
+                "digamma" if arg.lo > 0.0 => arg.increasing(name),
+                "gamma" | "digamma" => Ok(Interval::ENTIRE),
+                _ => Err(EvalError::UnknownFunction(name.clone())),
+            }
+        }
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => Ok(apply(inner, args)?.neg()),
+        Expr::BinaryOp(op, left, right) => binary(*op, apply(left, args)?, apply(right, args)?),
+        Expr::Call(outer, inner) => {
+            let inner = inner.iter().map(|g| apply(g, args)).collect::<Result<Vec<Interval>, EvalError>>()?;
+            apply(outer, &inner)
+        }
+        Expr::Derivative(func, indices) => Err(EvalError::UnknownFunction(derivative_name(func, indices))),
+        Expr::Matrix(_) | Expr::Equation(_, _) => Err(EvalError::NotScalar),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate::{evaluate, Environment};
+    use crate::parser::parse;
+
+    #[test]
+    fn test_enclosure_contains_samples() {
+        let domain = Interval::new(-2.0, 3.0);
+        let mut env = Environment::new();
+        for source in ["x^2 - 2*x", "sin(x) * cos(2*x)", "exp(-x^2) + atan(x)", "x^3 / (x^2 + 1)"] {
+            let expr = parse(source);
+            let enclosure = evaluate_interval(&expr, "x", domain).unwrap();
+            for i in 0..=100 {
+                let x = -2.0 + 5.0 * i as f64 / 100.0;
+                env.set_variable("x".to_string(), x);
+                assert!(enclosure.contains(evaluate(&expr, &env).unwrap()), "{} at {}", source, x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_interval_functions() {
+        // Gerade Potenzen sind nichtnegativ
+        let square = evaluate_interval(&parse("x^2"), "x", Interval::new(-1.0, 2.0)).unwrap();
+        assert!(square.lo == 0.0 && square.hi >= 4.0 && square.hi < 4.0 + 1e-12);
+        let sine = evaluate_interval(&parse("sin(x)"), "x", Interval::new(0.0, 3.0)).unwrap();
+        assert!(sine.hi == 1.0 && sine.lo <= 0.0 && sine.lo > -1e-12);
+    }
+
+    #[test]
+    fn test_elementary_library_enclosures() {
+        let domain = Interval::new(0.2, 0.9);
+        let mut env = Environment::new();
+        let sources = ["asin(x) + acos(x)", "sqrt(x) * cosh(x)", "atanh(x) - tanh(x)", "erf(x) + gamma(x + 2)", "log(2, x) + sec(x)"];
+        for source in sources {
+            let expr = parse(source);
+            let enclosure = evaluate_interval(&expr, "x", domain).unwrap();
+            for i in 0..=70 {
+                let x = 0.2 + i as f64 / 100.0;
+                env.set_variable("x".to_string(), x);
+                assert!(enclosure.contains(evaluate(&expr, &env).unwrap()), "{} at {}", source, x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_poles_and_domain() {
+        // Polstelle von 1/x und tan(x) im Intervall
+        assert_eq!(evaluate_interval(&parse("1/x"), "x", Interval::new(-1.0, 1.0)), Ok(Interval::ENTIRE));
+        assert_eq!(evaluate_interval(&parse("tan(x)"), "x", Interval::new(1.0, 2.0)), Ok(Interval::ENTIRE));
+        // ln nahe 0 ist nach unten unbeschränkt, ganz links von 0 nicht definiert
+        let log = evaluate_interval(&parse("ln(x)"), "x", Interval::new(0.0, 1.0)).unwrap();
+        assert!(log.lo == f64::NEG_INFINITY && log.hi >= 0.0);
+        assert!(evaluate_interval(&parse("ln(x)"), "x", Interval::new(-2.0, -1.0)).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::differentiate::depends_on;
+use crate::evaluate::{evaluate, Environment};
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+use crate::simplify::simplify;
+
+/// Grenzwert eines Ausdrucks: ein endlicher (symbolischer) Wert oder ±∞.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Limit {
+    Finite(Expr),
+    PositiveInfinity,
+    NegativeInfinity,
+}
+
+/// Bestimmt den Grenzwert von `expr` für `var → +∞` (`positive`) bzw. `var → -∞` mit einfachen
+/// Regeln für Summen, Produkte, Potenzen und elementare Funktionen, z.B. `exp(-x) → 0` oder
+/// `atan(x) → π/2`. Unbestimmte Formen wie `∞ - ∞` oder `0 · ∞` und oszillierende Funktionen
+/// ergeben `None`.
+pub fn limit_at_infinity(expr: &Expr, var: &str, positive: bool) -> Option<Limit> {
+    Some(match limit(expr, var, positive)? {
+        Limit::Finite(value) => Limit::Finite(simplify(value, false)),
+        infinite => infinite,
+    })
+}
+
+fn limit(expr: &Expr, var: &str, positive: bool) -> Option<Limit> {
+    if !depends_on(expr, var) {
+        return Some(Limit::Finite(expr.clone()));
+    }
+    match expr {
+        Expr::Var(_) => Some(infinity(positive)),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => Some(negate(limit(inner, var, positive)?)),
+        Expr::BinaryOp(op, left, right) => {
+            let (left, right) = (limit(left, var, positive)?, limit(right, var, positive)?);
+            match op {
+                BinaryOpKind::Add => add(left, right),
+                BinaryOpKind::Sub => add(left, negate(right)),
+                BinaryOpKind::Mul => multiply(left, right),
+                BinaryOpKind::Div => divide(left, right),
+                BinaryOpKind::Pow => power(left, right),
+            }
+        }
+        Expr::Call(func, args) => match (&**func, &args[..]) {
+            (Expr::Var(name), [arg]) => call_limit(name, limit(arg, var, positive)?),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn infinity(positive: bool) -> Limit {
+    if positive {
+        Limit::PositiveInfinity
+    } else {
+        Limit::NegativeInfinity
+    }
+}
+
+fn negate(limit: Limit) -> Limit {
+    match limit {
+        Limit::Finite(value) => Limit::Finite(Expr::UnaryOp(UnaryOpKind::Neg, Box::new(value))),
+        Limit::PositiveInfinity => Limit::NegativeInfinity,
+        Limit::NegativeInfinity => Limit::PositiveInfinity,
+    }
+}
+
+// Vorzeichen eines endlichen Grenzwerts; `None`, wenn er nicht numerisch ausgewertet werden kann
+fn signum(value: &Expr) -> Option<f64> {
+    let value = evaluate(value, &Environment::new()).ok()?;
+    if value.is_nan() {
+        return None;
+    }
+    Some(if value == 0.0 { 0.0 } else { value.signum() })
+}
+
+fn binary(op: BinaryOpKind, left: Expr, right: Expr) -> Limit {
+    Limit::Finite(Expr::BinaryOp(op, Box::new(left), Box::new(right)))
+}
+
+fn add(left: Limit, right: Limit) -> Option<Limit> {
+    match (left, right) {
+        (Limit::Finite(a), Limit::Finite(b)) => Some(binary(BinaryOpKind::Add, a, b)),
+        (Limit::Finite(_), infinite) | (infinite, Limit::Finite(_)) => Some(infinite),
+        (a, b) if a == b => Some(a),
+        // ∞ - ∞
+        _ => None,
+    }
+}
+
+fn multiply(left: Limit, right: Limit) -> Option<Limit> {
+    match (left, right) {
+        (Limit::Finite(a), Limit::Finite(b)) => Some(binary(BinaryOpKind::Mul, a, b)),
+        (Limit::Finite(c), infinite) | (infinite, Limit::Finite(c)) => match signum(&c)? {
+            // 0 · ∞
+            0.0 => None,
+            s if s > 0.0 => Some(infinite),
+            _ => Some(negate(infinite)),
+        },
+        (a, b) => Some(infinity(a == b)),
+    }
+}
+
+fn divide(left: Limit, right: Limit) -> Option<Limit> {
+    match (left, right) {
+        (Limit::Finite(a), Limit::Finite(b)) => match signum(&b)? {
+            // c/0
+            0.0 => None,
+            _ => Some(binary(BinaryOpKind::Div, a, b)),
+        },
+        (Limit::Finite(_), _) => Some(Limit::Finite(Expr::Number(0.0))),
+        (infinite, Limit::Finite(c)) => match signum(&c)? {
+            0.0 => None,
+            s if s > 0.0 => Some(infinite),
+            _ => Some(negate(infinite)),
+        },
+        // ∞/∞
+        _ => None,
+    }
+}
+
+fn power(base: Limit, exponent: Limit) -> Option<Limit> {
+    match (base, exponent) {
+        (Limit::Finite(a), Limit::Finite(b)) => Some(binary(BinaryOpKind::Pow, a, b)),
+        // c^(±∞) für eine Konstante c > 0, z.B. e^(-x)
+        (Limit::Finite(c), infinite) => {
+            let c = evaluate(&c, &Environment::new()).ok()?;
+            let grows = (c > 1.0) == (infinite == Limit::PositiveInfinity);
+            match c {
+                1.0 => Some(Limit::Finite(Expr::Number(1.0))),
+                c if c > 0.0 && grows => Some(Limit::PositiveInfinity),
+                c if c > 0.0 => Some(Limit::Finite(Expr::Number(0.0))),
+                _ => None,
+            }
+        }
+        // (±∞)^n für eine Konstante n
+        (infinite, Limit::Finite(n)) => {
+            let n = evaluate(&n, &Environment::new()).ok()?;
+            match n {
+                0.0 => Some(Limit::Finite(Expr::Number(1.0))),
+                n if n < 0.0 => Some(Limit::Finite(Expr::Number(0.0))),
+                _ if infinite == Limit::PositiveInfinity => Some(Limit::PositiveInfinity),
+                n if n.fract() == 0.0 => Some(infinity(n % 2.0 == 0.0)),
+                _ => None,
+            }
+        }
+        (Limit::PositiveInfinity, Limit::PositiveInfinity) => Some(Limit::PositiveInfinity),
+        _ => None,
+    }
+}
+
+fn call_limit(name: &str, arg: Limit) -> Option<Limit> {
+    let constant = |value: Expr| Some(Limit::Finite(value));
+    let half_pi = || Expr::BinaryOp(BinaryOpKind::Div, Box::new(Expr::Var("pi".to_string())), Box::new(Expr::Number(2.0)));
+    match (name, arg) {
+        // Stetige Funktionen an endlichen Stellen, sofern der Wert dort definiert ist
+        (_, Limit::Finite(value)) => {
+            let call = Expr::Call(Box::new(Expr::Var(name.to_string())), vec![value]);
+            evaluate(&call, &Environment::new()).ok().filter(|v| v.is_finite())?;
+            constant(call)
+        }
+        ("exp" | "ln" | "sqrt" | "sinh" | "asinh" | "cosh" | "abs" | "acosh", Limit::PositiveInfinity) => Some(Limit::PositiveInfinity),
+        ("cosh" | "abs", Limit::NegativeInfinity) => Some(Limit::PositiveInfinity),
+        ("sinh" | "asinh", Limit::NegativeInfinity) => Some(Limit::NegativeInfinity),
+        ("exp", Limit::NegativeInfinity) => constant(Expr::Number(0.0)),
+        ("atan", Limit::PositiveInfinity) => constant(half_pi()),
+        ("atan", Limit::NegativeInfinity) => constant(Expr::UnaryOp(UnaryOpKind::Neg, Box::new(half_pi()))),
+        ("tanh", Limit::PositiveInfinity) => constant(Expr::Number(1.0)),
+        ("tanh", Limit::NegativeInfinity) => constant(Expr::Number(-1.0)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use crate::render::render_latex;
+
+    fn at_infinity(source: &str, positive: bool) -> Option<Limit> {
+        limit_at_infinity(&parse(source), "x", positive)
+    }
+
+    #[test]
+    fn test_finite_limits() {
+        assert_eq!(at_infinity("-exp(-x)", true), Some(Limit::Finite(Expr::Number(0.0))));
+        assert_eq!(at_infinity("-(e^(-x))", true), Some(Limit::Finite(Expr::Number(0.0))));
+        assert_eq!(at_infinity("3 - 1/x^2", true), Some(Limit::Finite(Expr::Number(3.0))));
+        assert_eq!(at_infinity("exp(x)", false), Some(Limit::Finite(Expr::Number(0.0))));
+        let Some(Limit::Finite(value)) = at_infinity("atan(x)", false) else {
+            panic!("expected a finite limit");
+        };
+        assert_eq!(render_latex(&value), "-\\frac{\\pi}{2}");
+    }
+
+    #[test]
+    fn test_infinite_and_undetermined_limits() {
+        assert_eq!(at_infinity("ln(x) + 1", true), Some(Limit::PositiveInfinity));
+        assert_eq!(at_infinity("x^3", false), Some(Limit::NegativeInfinity));
+        assert_eq!(at_infinity("-2*x^2", false), Some(Limit::NegativeInfinity));
+        // ∞ - ∞, 0 · ∞ und Schwingungen sind nicht bestimmbar
+        assert_eq!(at_infinity("ln(x) - ln(x + 1)", true), None);
+        assert_eq!(at_infinity("x*exp(-x)", true), None);
+        assert_eq!(at_infinity("cos(x)", true), None);
+    }
+}
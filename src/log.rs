@@ -0,0 +1,132 @@
+use crate::assumptions::sign;
+use crate::parser::{BinaryOpKind, Expr};
+use crate::simplify::{build_product, build_sum, collect, simplify, terms};
+
+/// Zerlegt Logarithmen von Produkten, Quotienten und Potenzen:
+/// `ln(a*b) = ln(a) + ln(b)`, `ln(a/b) = ln(a) - ln(b)` und `ln(a^n) = n ln(a)`.
+/// Die Regeln werden nur angewendet, wenn `a` und `b` laut den Annahmen positiv sind.
+pub fn expand_log(expr: Expr) -> Expr {
+    fn expand_calls(expr: Expr) -> Expr {
+        match expr {
+            Expr::BinaryOp(op, left, right) => {
+                Expr::BinaryOp(op, Box::new(expand_calls(*left)), Box::new(expand_calls(*right)))
+            }
+            Expr::UnaryOp(op, inner) => Expr::UnaryOp(op, Box::new(expand_calls(*inner))),
+            Expr::Call(func, args) => {
+                let args: Vec<Expr> = args.into_iter().map(expand_calls).collect();
+                match (*func, args.len()) {
+                    (Expr::Var(name), 1) if name == "ln" => expand_ln(args[0].clone()),
+                    (func, _) => Expr::Call(Box::new(func), args),
+                }
+            }
+            _ => expr,
+        }
+    }
+    collect(expand_calls(simplify(expr, false)))
+}
+
+/// Fasst Summen von Logarithmen zu einem einzigen Logarithmus zusammen:
+/// `a ln(x) + b ln(y) = ln(x^a y^b)`.
+pub fn combine_log(expr: Expr) -> Expr {
+    let expr = match simplify(expr, false) {
+        Expr::BinaryOp(op, left, right) => Expr::BinaryOp(op, Box::new(combine_log(*left)), Box::new(combine_log(*right))),
+        Expr::UnaryOp(op, inner) => Expr::UnaryOp(op, Box::new(combine_log(*inner))),
+        Expr::Call(func, args) => Expr::Call(func, args.into_iter().map(combine_log).collect()),
+        expr => expr,
+    };
+    if !matches!(expr, Expr::BinaryOp(_, _, _) | Expr::UnaryOp(_, _)) {
+        return expr;
+    }
+
+    // Die Summanden der Form c * ln(u) werden zu einem Produkt u^c zusammengefasst
+    let mut logarithms: Vec<(Expr, f64)> = vec![];
+    let mut others: Vec<(f64, Expr)> = vec![];
+    for (coefficient, term) in terms(&expr) {
+        match as_ln(&term) {
+            Some(argument) => logarithms.push((argument.clone(), coefficient)),
+            None => others.push((coefficient, term)),
+        }
+    }
+    if logarithms.is_empty() || (logarithms.len() == 1 && logarithms[0].1 == 1.0) {
+        return expr;
+    }
+    let argument = collect(build_product(logarithms));
+    let mut result = vec![(1.0, ln(argument))];
+    result.extend(others);
+    build_sum(result)
+}
+
+// ln eines Produkts, Quotienten oder einer Potenz wird rekursiv zerlegt,
+// sofern die Faktoren positiv sind (sonst wäre z.B. ln(x^2) = 2 ln(x) für x < 0 falsch)
+fn expand_ln(argument: Expr) -> Expr {
+    match argument {
+        Expr::BinaryOp(BinaryOpKind::Mul, left, right) if sign(&left).is_positive() && sign(&right).is_positive() => {
+            Expr::BinaryOp(BinaryOpKind::Add, Box::new(expand_ln(*left)), Box::new(expand_ln(*right)))
+        }
+        Expr::BinaryOp(BinaryOpKind::Div, left, right) if sign(&left).is_positive() && sign(&right).is_positive() => {
+            Expr::BinaryOp(BinaryOpKind::Sub, Box::new(expand_ln(*left)), Box::new(expand_ln(*right)))
+        }
+        Expr::BinaryOp(BinaryOpKind::Pow, base, exponent) if sign(&base).is_positive() => {
+            Expr::BinaryOp(BinaryOpKind::Mul, exponent, Box::new(expand_ln(*base)))
+        }
+        Expr::Call(func, args) if matches!(&*func, Expr::Var(name) if name == "exp") && args.len() == 1 => args[0].clone(),
+        Expr::Number(1.0) => Expr::Number(0.0),
+        argument => ln(argument),
+    }
+}
+
+fn ln(argument: Expr) -> Expr {
+    Expr::Call(Box::new(Expr::Var("ln".to_string())), vec![argument])
+}
+
+// Erkennt ln(u) und gibt u zurück
+fn as_ln(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Call(func, args) if matches!(&**func, Expr::Var(name) if name == "ln") && args.len() == 1 => Some(&args[0]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assumptions::{assume, forget_all, Property};
+    use crate::render::render_latex;
+
+    fn var(name: &str) -> Expr {
+        Expr::Var(name.to_string())
+    }
+
+    #[test]
+    fn test_expand_log() {
+        // ln(x^2 * y) = 2 ln(x) + ln(y) für x, y > 0
+        let expr = ln(Expr::BinaryOp(
+            BinaryOpKind::Mul,
+            Box::new(Expr::BinaryOp(BinaryOpKind::Pow, Box::new(var("x")), Box::new(Expr::Number(2.0)))),
+            Box::new(var("y")),
+        ));
+        assume("x".to_string(), Property::Positive);
+        assume("y".to_string(), Property::Positive);
+        assert_eq!(render_latex(&expand_log(expr.clone())), "2 \\cdot \\ln\\left(x\\right)+\\ln\\left(y\\right)");
+        // Ohne Annahmen bleibt der Logarithmus unverändert
+        forget_all();
+        assert_eq!(expand_log(expr.clone()), simplify(expr, false));
+    }
+
+    #[test]
+    fn test_combine_log() {
+        // ln(x) - ln(y) = ln(x/y)
+        let expr = Expr::BinaryOp(BinaryOpKind::Sub, Box::new(ln(var("x"))), Box::new(ln(var("y"))));
+        assert_eq!(render_latex(&combine_log(expr)), "\\ln\\left(\\frac{x}{y}\\right)");
+    }
+
+    #[test]
+    fn test_exp_ln_inverse() {
+        let exp = |argument: Expr| Expr::Call(Box::new(var("exp")), vec![argument]);
+        assert_eq!(simplify(exp(ln(var("x"))), false), var("x"));
+        assert_eq!(simplify(ln(exp(var("x"))), false), var("x"));
+        // exp(a) * exp(b) = exp(a+b)
+        let product = Expr::BinaryOp(BinaryOpKind::Mul, Box::new(exp(var("a"))), Box::new(exp(var("b"))));
+        assert_eq!(render_latex(&simplify(product, false)), "\\exp\\left(a+b\\right)");
+    }
+}
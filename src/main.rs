@@ -1,10 +1,22 @@
-use crate::differentiate::differentiate;
-use crate::render::{render_latex};
+use crate::differentiate::{differentiate, differentiate_n, parse_orders};
+use crate::render::{render_derivative_operator, render_latex};
 use crate::scanner::Scanner;
 use crate::simplify::simplify;
-use crate::integrate::{integrate_polynomial, approx_integral};
+use crate::integrate::{definite_integral, integrate, numerical_integral};
+use crate::quadrature::DEFAULT_TOLERANCE;
+use crate::multiple::{multiple_integral, Bound};
+use crate::apart::apart;
+use crate::trig::{trigexpand, trigreduce, trigsimp};
+use crate::log::{combine_log, expand_log};
+use crate::assumptions::{assume_relation, forget, forget_all};
+use crate::complexity::{simplest_form, Measure};
 use crate::parser::Expr;
-use crate::plot::{plot, substitute_for_variable};
+use crate::plot::plot;
+use crate::evaluate::{evaluate, Environment};
+use crate::autodiff::{forward_gradient, reverse_gradient};
+use crate::steps::{render_steps, traced};
+use crate::implicit::{implicit_diff, implicit_diff_n};
+use crate::vector::{curl, divergence, gradient, hessian, jacobian, laplacian};
 use wasm_bindgen::prelude::*;
 
 mod scanner;
@@ -16,6 +28,26 @@ mod integrate;
 mod substitute;
 mod constants;
 mod plot;
+mod polynomial;
+mod apart;
+mod trig;
+mod log;
+mod assumptions;
+mod complexity;
+mod evaluate;
+mod compile;
+mod interval;
+mod vector;
+mod implicit;
+mod autodiff;
+mod steps;
+mod risch;
+mod quadrature;
+mod limit;
+mod multiple;
+mod random;
+mod rational;
+mod verify;
 
 fn main() {}
 
@@ -40,7 +72,97 @@ pub fn differentiate_expression(expression: String, variable: String) -> String
 }
 
 #[wasm_bindgen]
-pub fn integrate_expression(expression: String, variable: String, lower: f64, upper: f64) -> String {
+pub fn differentiate_steps_expression(expression: String, variable: String) -> String {
+    // Ableiten und dabei jede angewendete Regel als LaTeX-Zeile aufzeichnen
+    let (_, steps) = traced(|| differentiate(parser::parse(&expression), variable));
+    render_steps(&steps)
+}
+
+#[wasm_bindgen]
+pub fn differentiate_expression_n(expression: String, variables: String) -> Result<String, String> {
+    // Ableitungsordnungen wie "x^2, y" lesen und schrittweise ableiten
+    let orders = parse_orders(&variables)?;
+    let expression = parser::parse(&expression);
+    let result = differentiate_n(expression.clone(), &orders);
+    Ok(format!(
+        "{}\\left({}\\right)={}",
+        render_derivative_operator(&orders),
+        render_latex(&expression),
+        render_latex(&result)
+    ))
+}
+
+#[wasm_bindgen]
+pub fn implicit_diff_expression(equation: String, dependent: String, independent: String, order: Option<usize>) -> Result<String, String> {
+    // Gleichung wie "x^2 + y^2 = 1" lesen und d^n y/dx^n mit y = y(x) bestimmen, ohne Ordnung dy/dx
+    let equation = parser::parse(&equation);
+    let (result, order) = match order {
+        Some(order) => (implicit_diff_n(&equation, &dependent, &independent, order)?, order),
+        None => (implicit_diff(&equation, &dependent, &independent)?, 1),
+    };
+    let power = |symbol: &str| if order == 1 { symbol.to_string() } else { format!("{}^{{{}}}", symbol, order) };
+    Ok(format!("\\frac{{{}{}}}{{d{}}}={}", power("d"), dependent, power(&independent), render_latex(&result)))
+}
+
+#[wasm_bindgen]
+pub fn gradient_expression(expression: String, variables: String) -> String {
+    // Variablen wie "x, y, z" lesen und den Gradienten als Spaltenvektor rendern
+    render_latex(&gradient(&parser::parse(&expression), &parse_variables(&variables)))
+}
+
+#[wasm_bindgen]
+pub fn jacobian_expression(expressions: String, variables: String) -> String {
+    // Komponenten werden durch ";" getrennt, z.B. "x*y; x + y"
+    render_latex(&jacobian(&parse_components(&expressions), &parse_variables(&variables)))
+}
+
+#[wasm_bindgen]
+pub fn hessian_expression(expression: String, variables: String) -> String {
+    render_latex(&hessian(&parser::parse(&expression), &parse_variables(&variables)))
+}
+
+#[wasm_bindgen]
+pub fn divergence_expression(expressions: String, variables: String) -> Result<String, String> {
+    Ok(render_latex(&divergence(&parse_components(&expressions), &parse_variables(&variables))?))
+}
+
+#[wasm_bindgen]
+pub fn curl_expression(expressions: String, variables: String) -> Result<String, String> {
+    Ok(render_latex(&curl(&parse_components(&expressions), &parse_variables(&variables))?))
+}
+
+#[wasm_bindgen]
+pub fn laplacian_expression(expression: String, variables: String) -> String {
+    render_latex(&laplacian(&parser::parse(&expression), &parse_variables(&variables)))
+}
+
+#[wasm_bindgen]
+pub fn numeric_gradient_expression(expression: String, variables: String, point: Vec<f64>, reverse: bool) -> Result<Vec<f64>, String> {
+    // Wert und Gradient ohne symbolische Ableitung, das Ergebnis ist [f, ∂f/∂x_1, ..., ∂f/∂x_n]
+    let expression = parser::parse(&expression);
+    let variables = parse_variables(&variables);
+    let env = Environment::new();
+    let result = if reverse {
+        reverse_gradient(&expression, &variables, &point, &env)
+    } else {
+        forward_gradient(&expression, &variables, &point, &env)
+    }
+    .map_err(|error| error.to_string())?;
+    Ok(std::iter::once(result.value).chain(result.gradient).collect())
+}
+
+// Durch Kommas getrennte Variablen
+fn parse_variables(variables: &str) -> Vec<String> {
+    variables.split(',').map(str::trim).filter(|var| !var.is_empty()).map(str::to_string).collect()
+}
+
+// Durch Semikolons getrennte Komponenten eines Vektorfelds
+fn parse_components(expressions: &str) -> Vec<Expr> {
+    expressions.split(';').filter(|component| !component.trim().is_empty()).map(parser::parse).collect()
+}
+
+#[wasm_bindgen]
+pub fn integrate_expression(expression: String, variable: String, lower: f64, upper: f64) -> Result<String, String> {
     // Scanner initialisieren und Token sammeln
     let mut scanner = Scanner::new(&expression);
     let mut tokens = vec![];
@@ -54,11 +176,31 @@ pub fn integrate_expression(expression: String, variable: String, lower: f64, up
     let mut parser = parser::Parser::new(tokens);
     let expression = parser.expression();
 
-    // Ausdruck integrieren und als LaTeX rendern
-    if let Ok(integral) = approx_integral(expression.clone(), variable, lower, upper) {
-        return render_latex(&simplify(integral.clone(), false));
-    }
-    "Error".to_string()
+    // Exakt über eine Stammfunktion integrieren, sonst numerisch; das Verfahren wird mit angegeben
+    let integral = definite_integral(expression, variable, lower, upper)?;
+    Ok(format!("{}\\quad\\text{{({})}}", render_latex(&integral.value), integral.method.description()))
+}
+
+#[wasm_bindgen]
+pub fn numerical_integral_expression(expression: String, variable: String, lower: f64, upper: f64, tolerance: Option<f64>) -> Result<Vec<f64>, String> {
+    // Adaptive Quadratur mit wählbarer Toleranz, das Ergebnis ist [Wert, geschätzter Fehler]
+    let expression = parser::parse(&expression);
+    let result = numerical_integral(&expression, &variable, lower, upper, tolerance.unwrap_or(DEFAULT_TOLERANCE))?;
+    Ok(vec![result.value, result.error])
+}
+
+#[wasm_bindgen]
+pub fn multiple_integral_expression(expression: String, bounds: String) -> Result<String, String> {
+    // Grenzen im Format "y, 0, x; x, 0, 1", die innerste Variable zuerst wie in dy dx
+    let bounds = bounds
+        .split(';')
+        .map(|bound| match bound.split(',').map(str::trim).collect::<Vec<_>>()[..] {
+            [var, lower, upper] => Ok(Bound { var: var.to_string(), lower: parser::parse(lower), upper: parser::parse(upper) }),
+            _ => Err(format!("Error: Invalid integration bound '{}'", bound.trim())),
+        })
+        .collect::<Result<Vec<Bound>, String>>()?;
+    let integral = multiple_integral(&parser::parse(&expression), &bounds, DEFAULT_TOLERANCE)?;
+    Ok(format!("{}\\quad\\text{{({})}}", render_latex(&integral.value), integral.method.description()))
 }
 
 #[wasm_bindgen]
@@ -76,16 +218,22 @@ pub fn find_antiderivative(expression: String, variable: String) -> Result<Strin
     let mut parser = parser::Parser::new(tokens);
     let expression = parser.expression();
 
-    // Ausdruck integrieren und als LaTeX rendern
-    let integral = integrate_polynomial(expression.clone(), variable);
-    if let Ok(integral) = integral {
-        return Ok(render_latex(&simplify(integral.clone(), false)));
-    }
-    Err(integral.err().unwrap())
+    // Stammfunktion mit Tabelle, Substitution, Partialbrüchen und partieller Integration suchen
+    let integral = integrate(expression, variable)?;
+    Ok(render_latex(&integral))
+}
+
+#[wasm_bindgen]
+pub fn apart_expression(expression: String, variable: String) -> Result<String, String> {
+    let expression = parser::parse(&expression);
+
+    // Partialbruchzerlegung durchführen und als LaTeX rendern
+    let result = apart(expression, variable)?;
+    Ok(render_latex(&result))
 }
 
 #[wasm_bindgen]
-pub fn simplify_expression(expression: String) -> String {
+pub fn simplify_expression(expression: String, measure: Option<String>) -> Result<String, String> {
     // Scanner initialisieren und Token sammeln
     let mut scanner = Scanner::new(&expression);
     let mut tokens = vec![];
@@ -99,8 +247,92 @@ pub fn simplify_expression(expression: String) -> String {
     let mut parser = parser::Parser::new(tokens);
     let expression = parser.expression();
 
-    // Ausdruck vereinfachen und als LaTeX rendern
-    render_latex(&simplify(expression.clone(), false))
+    // Einfachste Form nach dem gewählten Komplexitätsmaß suchen und als LaTeX rendern
+    let measure = match measure {
+        Some(name) => Measure::from_name(&name)?,
+        None => Measure::NodeCount,
+    };
+    Ok(render_latex(&simplest_form(expression, measure)))
+}
+
+#[wasm_bindgen]
+pub fn trigsimp_expression(expression: String) -> String {
+    let expression = parser::parse(&expression);
+
+    // Trigonometrische Identitäten anwenden und als LaTeX rendern
+    render_latex(&trigsimp(expression))
+}
+
+#[wasm_bindgen]
+pub fn trigexpand_expression(expression: String) -> String {
+    let expression = parser::parse(&expression);
+
+    // Additionstheoreme anwenden und als LaTeX rendern
+    render_latex(&trigexpand(expression))
+}
+
+#[wasm_bindgen]
+pub fn trigreduce_expression(expression: String) -> String {
+    let expression = parser::parse(&expression);
+
+    // Potenzen und Produkte von sin/cos reduzieren und als LaTeX rendern
+    render_latex(&trigreduce(expression))
+}
+
+#[wasm_bindgen]
+pub fn expand_log_expression(expression: String) -> String {
+    let expression = parser::parse(&expression);
+
+    // Logarithmen zerlegen und als LaTeX rendern
+    render_latex(&expand_log(expression))
+}
+
+#[wasm_bindgen]
+pub fn combine_log_expression(expression: String) -> String {
+    let expression = parser::parse(&expression);
+
+    // Logarithmen zusammenfassen und als LaTeX rendern
+    render_latex(&combine_log(expression))
+}
+
+#[wasm_bindgen]
+pub fn assume_expression(relation: String) -> Result<String, String> {
+    // Annahme wie "x > 0" oder "n ∈ ℤ" für alle folgenden Umformungen merken
+    let (variable, property) = assume_relation(&relation)?;
+    Ok(format!("{} {}", variable, property))
+}
+
+#[wasm_bindgen]
+pub fn forget_assumptions(variable: String) {
+    // Ohne Variable werden alle Annahmen entfernt
+    if variable.is_empty() {
+        forget_all();
+    } else {
+        forget(&variable);
+    }
+}
+
+#[wasm_bindgen]
+pub fn evaluate_expression(expression: String, definitions: String) -> Result<f64, String> {
+    // Definitionen zeilenweise lesen: "x = 2" setzt eine Variable, "f(t) = t^2" definiert eine Funktion
+    let mut env = Environment::new();
+    for definition in definitions.lines().filter(|line| !line.trim().is_empty()) {
+        let (left, right) = definition
+            .split_once('=')
+            .ok_or_else(|| format!("Error: Expected '=' in definition: {}", definition))?;
+        let body = parser::parse(right);
+        match left.trim().split_once('(') {
+            Some((name, parameters)) => {
+                let parameters = parameters.trim_end_matches(')').split(',').map(|p| p.trim().to_string()).collect();
+                env.define_function(name.trim().to_string(), parameters, body);
+            }
+            None => {
+                let value = evaluate(&body, &env).map_err(|error| error.to_string())?;
+                env.set_variable(left.trim().to_string(), value);
+            }
+        }
+    }
+    evaluate(&parser::parse(&expression), &env).map_err(|error| error.to_string())
 }
 
 #[wasm_bindgen]
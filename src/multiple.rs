@@ -0,0 +1,208 @@
+use crate::compile::{compile, CompiledFn};
+use crate::differentiate::depends_on;
+use crate::evaluate::{evaluate, Environment};
+use crate::integrate::{integrate, DefiniteIntegral, IntegrationMethod};
+use crate::parser::{BinaryOpKind, Expr};
+use crate::polynomial::exact_number;
+use crate::quadrature::{integrate_numerically, Quadrature};
+use crate::random::Xorshift;
+use crate::simplify::{collect, simplify};
+use crate::substitute::substitute;
+
+/// Eine Integrationsvariable mit ihren Grenzen. Die Grenzen dürfen von den weiter außen liegenden
+/// Variablen abhängen, z.B. `y` von 0 bis `x` in `∫_0^1 ∫_0^x xy dy dx`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bound {
+    pub var: String,
+    pub lower: Expr,
+    pub upper: Expr,
+}
+
+// Bis zu dieser Dimension wird verschachtelt adaptiv integriert, darüber mit Monte-Carlo
+const MAX_NESTED_DIMENSION: usize = 3;
+
+const MONTE_CARLO_SAMPLES: usize = 100_000;
+
+/// Berechnet das Mehrfachintegral von `expr` über die Variablen in `bounds`, die innerste zuerst wie in
+/// `dy dx`. Zuerst wird iteriert symbolisch integriert; das Ergebnis wird numerisch gegengeprüft, weil
+/// Singularitäten im Inneren des Bereichs bei variablen Grenzen nicht vorab ausgeschlossen werden können.
+/// Ohne symbolisches Ergebnis wird bis zur Dimension 3 verschachtelt mit adaptiver Quadratur und darüber
+/// mit Monte-Carlo integriert.
+pub fn multiple_integral(expr: &Expr, bounds: &[Bound], tolerance: f64) -> Result<DefiniteIntegral, String> {
+    if bounds.is_empty() {
+        return Err("Error: Expected at least one integration variable".to_string());
+    }
+    // Die Grenzen einer Variablen dürfen weder sie selbst noch weiter innen liegende Variablen enthalten
+    for (k, bound) in bounds.iter().enumerate() {
+        if let Some(inner) = bounds[..=k].iter().find(|inner| depends_on(&bound.lower, &inner.var) || depends_on(&bound.upper, &inner.var)) {
+            return Err(format!("Error: The bounds of {} must not depend on {}", bound.var, inner.var));
+        }
+    }
+    let exact = iterated_integral(expr, bounds);
+    let (numerical, method) = match numerical_integral(expr, bounds, tolerance) {
+        Ok(result) => result,
+        // Mit freien Parametern ist nur das symbolische Ergebnis möglich
+        Err(error) => {
+            return match exact {
+                Some(value) if evaluate(&value, &Environment::new()).is_err() => Ok(antiderivative(value)),
+                _ => Err(error),
+            };
+        }
+    };
+    if let Some(value) = exact {
+        let agrees = evaluate(&value, &Environment::new()).is_ok_and(|exact| {
+            (exact - numerical.value).abs() <= (5.0 * numerical.error).max(1e-6 * numerical.value.abs().max(1.0))
+        });
+        if agrees {
+            return Ok(antiderivative(value));
+        }
+    }
+    Ok(DefiniteIntegral { value: Expr::Number(numerical.value), method })
+}
+
+fn antiderivative(value: Expr) -> DefiniteIntegral {
+    let value = match value {
+        Expr::Number(n) => exact_number(n),
+        value => value,
+    };
+    DefiniteIntegral { value, method: IntegrationMethod::Antiderivative }
+}
+
+// Integriert von innen nach außen und setzt jeweils die Grenzen in die Stammfunktion ein
+fn iterated_integral(expr: &Expr, bounds: &[Bound]) -> Option<Expr> {
+    let mut result = expr.clone();
+    for bound in bounds {
+        let antiderivative = integrate(result, bound.var.clone()).ok()?;
+        let at = |value: &Expr| substitute(antiderivative.clone(), bound.var.clone(), value.clone());
+        result = collect(simplify(Expr::BinaryOp(BinaryOpKind::Sub, Box::new(at(&bound.upper)), Box::new(at(&bound.lower))), false));
+    }
+    Some(result)
+}
+
+// Übersetzt Integrand und Grenzen; die Variablen sind von außen nach innen geordnet, und die Grenzen
+// einer Variablen dürfen nur die weiter außen liegenden verwenden.
+fn numerical_integral(expr: &Expr, bounds: &[Bound], tolerance: f64) -> Result<(Quadrature, IntegrationMethod), String> {
+    let vars: Vec<String> = bounds.iter().rev().map(|bound| bound.var.clone()).collect();
+    let failed = |error: crate::evaluate::EvalError| format!("Integration failed: {}", error);
+    let integrand = compile(expr, &vars).map_err(failed)?;
+    let limits = bounds
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(k, bound)| Ok((compile(&bound.lower, &vars[..k]).map_err(failed)?, compile(&bound.upper, &vars[..k]).map_err(failed)?)))
+        .collect::<Result<Vec<(CompiledFn, CompiledFn)>, String>>()?;
+    if bounds.len() <= MAX_NESTED_DIMENSION {
+        Ok((nested(&integrand, &limits, vec![], tolerance)?, IntegrationMethod::Numerical))
+    } else {
+        Ok((monte_carlo(&integrand, &limits)?, IntegrationMethod::MonteCarlo))
+    }
+}
+
+// Verschachtelte adaptive Quadratur: Der Integrand der äußeren Integration ist das innere Integral
+// bei festen Werten der äußeren Variablen in `prefix`.
+fn nested(integrand: &CompiledFn, limits: &[(CompiledFn, CompiledFn)], prefix: Vec<f64>, tolerance: f64) -> Result<Quadrature, String> {
+    let failed = |error: crate::evaluate::EvalError| format!("Integration failed: {}", error);
+    let (lower, upper) = &limits[prefix.len()];
+    let (lower, upper) = (lower.call(&prefix).map_err(failed)?, upper.call(&prefix).map_err(failed)?);
+    let innermost = prefix.len() + 1 == limits.len();
+    integrate_numerically(
+        |value| {
+            let mut point = prefix.clone();
+            point.push(value);
+            if innermost {
+                integrand.call(&point).map_err(failed)
+            } else {
+                Ok(nested(integrand, limits, point, tolerance)?.value)
+            }
+        },
+        lower,
+        upper,
+        tolerance,
+    )
+}
+
+// Monte-Carlo-Integration: Jede Variable wird gleichverteilt zwischen ihren (von den äußeren Variablen
+// abhängigen) Grenzen gezogen und der Funktionswert mit dem Produkt der Intervalllängen gewichtet.
+// Der Fehler wird mit der Standardabweichung des Mittelwerts geschätzt.
+fn monte_carlo(integrand: &CompiledFn, limits: &[(CompiledFn, CompiledFn)]) -> Result<Quadrature, String> {
+    let failed = |error: crate::evaluate::EvalError| format!("Integration failed: {}", error);
+    let mut random = Xorshift::new(0x2545_f491_4f6c_dd1d);
+    let (mut sum, mut sum_of_squares) = (0.0, 0.0);
+    let mut point = Vec::with_capacity(limits.len());
+    for _ in 0..MONTE_CARLO_SAMPLES {
+        point.clear();
+        let mut weight = 1.0;
+        for (lower, upper) in limits {
+            let (lower, upper) = (lower.call(&point).map_err(failed)?, upper.call(&point).map_err(failed)?);
+            if !lower.is_finite() || !upper.is_finite() {
+                return Err("Error: Monte Carlo integration needs finite bounds".to_string());
+            }
+            weight *= upper - lower;
+            point.push(random.uniform(lower, upper));
+        }
+        let value = weight * integrand.call(&point).map_err(failed)?;
+        sum += value;
+        sum_of_squares += value * value;
+    }
+    let n = MONTE_CARLO_SAMPLES as f64;
+    let mean = sum / n;
+    let variance = (sum_of_squares / n - mean * mean).max(0.0);
+    Ok(Quadrature { value: mean, error: (variance / n).sqrt() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use crate::render::render_latex;
+
+    fn bound(var: &str, lower: &str, upper: &str) -> Bound {
+        Bound { var: var.to_string(), lower: parse(lower), upper: parse(upper) }
+    }
+
+    fn number(integral: &DefiniteIntegral) -> f64 {
+        evaluate(&integral.value, &Environment::new()).unwrap()
+    }
+
+    #[test]
+    fn test_iterated_symbolic_integral() {
+        // ∫_0^1 ∫_0^x xy dy dx = ∫_0^1 x^3/2 dx = 1/8
+        let integral = multiple_integral(&parse("x*y"), &[bound("y", "0", "x"), bound("x", "0", "1")], 1e-10).unwrap();
+        assert_eq!(integral.method, IntegrationMethod::Antiderivative);
+        assert_eq!(render_latex(&integral.value), "\\frac{1}{8}");
+        let cube = [bound("z", "0", "1"), bound("y", "0", "1"), bound("x", "0", "1")];
+        let integral = multiple_integral(&parse("x + y + z"), &cube, 1e-10).unwrap();
+        assert_eq!(render_latex(&integral.value), "\\frac{3}{2}");
+        // Mit einem Parameter bleibt das Ergebnis symbolisch
+        let integral = multiple_integral(&parse("a*x"), &[bound("y", "0", "1"), bound("x", "0", "2")], 1e-10).unwrap();
+        assert_eq!(render_latex(&integral.value), "2 \\cdot a");
+    }
+
+    #[test]
+    fn test_numerical_fallback() {
+        // ∫_0^1 ∫_0^1 e^(xy) dy dx = Σ 1/(n n!)
+        let integral = multiple_integral(&parse("exp(x*y)"), &[bound("y", "0", "1"), bound("x", "0", "1")], 1e-10).unwrap();
+        assert_eq!(integral.method, IntegrationMethod::Numerical);
+        assert!((number(&integral) - 1.3179021514544038).abs() < 1e-9);
+        // Viertelkreis: ∫_0^1 ∫_0^sqrt(1-x^2) e^(-(x^2+y^2)) dy dx = π/4 (1 - 1/e)
+        let region = [bound("y", "0", "sqrt(1 - x^2)"), bound("x", "0", "1")];
+        let integral = multiple_integral(&parse("exp(-(x^2 + y^2))"), &region, 1e-10).unwrap();
+        assert!((number(&integral) - std::f64::consts::FRAC_PI_4 * (1.0 - (-1.0f64).exp())).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_monte_carlo() {
+        // (∫_0^1 e^(-t^2) dt)^4 in vier Dimensionen
+        let bounds: Vec<Bound> = ["a", "b", "c", "d"].iter().map(|var| bound(var, "0", "1")).collect();
+        let integral = multiple_integral(&parse("exp(-(a^2 + b^2 + c^2 + d^2))"), &bounds, 1e-10).unwrap();
+        assert_eq!(integral.method, IntegrationMethod::MonteCarlo);
+        assert!((number(&integral) - 0.7468241328124271f64.powi(4)).abs() < 2e-3);
+    }
+
+    #[test]
+    fn test_invalid_bounds() {
+        // Die Grenzen dürfen nur äußere Variablen enthalten
+        assert!(multiple_integral(&parse("x*y"), &[bound("y", "0", "1"), bound("x", "0", "y")], 1e-10).is_err());
+        assert!(multiple_integral(&parse("x"), &[], 1e-10).is_err());
+    }
+}
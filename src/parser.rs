@@ -1,4 +1,4 @@
-use crate::scanner::{Token, TokenType};
+use crate::scanner::{Scanner, Token, TokenType};
 
 // Definiert die Arten von binären Operatoren
 #[derive(Debug, PartialEq, Copy, Clone, PartialOrd)]
@@ -24,6 +24,13 @@ pub enum Expr {
     Var(String),
     Call(Box<Expr>, Vec<Expr>),
     UnaryOp(UnaryOpKind, Box<Expr>),
+    /// Unausgewertete partielle Ableitung einer Funktion nach ihren Argumenten (0-basiert),
+    /// z.B. `Derivative(f, [0])` für f' oder `Derivative(f, [0, 1])` für ∂_1 ∂_2 f
+    Derivative(Box<Expr>, Vec<usize>),
+    /// Matrix als Liste von Zeilen, Vektoren sind Spaltenvektoren mit einem Eintrag pro Zeile
+    Matrix(Vec<Vec<Expr>>),
+    /// Gleichung `links = rechts`
+    Equation(Box<Expr>, Box<Expr>),
 }
 
 // Parser-Struktur
@@ -46,6 +53,15 @@ impl<'src> Parser<'src> {
         return self.addition();
     }
 
+    // Parst eine Gleichung wie `x^2 + y^2 = 1` oder einen einzelnen Ausdruck
+    pub fn equation(&mut self) -> Expr {
+        let left = self.expression();
+        if self.match_token(TokenType::Equal) {
+            return Expr::Equation(Box::new(left), Box::new(self.expression()));
+        }
+        left
+    }
+
     // Parst eine Addition oder Subtraktion
     fn addition(&mut self) -> Expr {
         let mut value = self.multiplication();
@@ -229,6 +245,20 @@ impl<'src> Parser<'src> {
     }
 }
 
+/// Scannt und parst einen Ausdruck oder eine Gleichung; gemeinsame Hilfsfunktion für die WebAssembly-Schnittstelle und die Tests.
+pub fn parse(source: &str) -> Expr {
+    let mut scanner = Scanner::new(source);
+    let mut tokens = vec![];
+    loop {
+        let token = scanner.scan_token();
+        tokens.push(token);
+        if token.kind == TokenType::Eof {
+            break;
+        }
+    }
+    Parser::new(tokens).equation()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::scanner::Scanner;
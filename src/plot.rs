@@ -1,30 +1,9 @@
-use crate::simplify::simplify;
+use crate::compile::compile;
+use crate::interval::{evaluate_interval, Interval};
 use crate::parser::{BinaryOpKind, Expr};
-use std::collections::HashMap;
 use std::io::Cursor;
 use image::{RgbImage, Rgb, GenericImage, ImageFormat};
 
-// Ersetzt Variablen in einem Ausdruck durch gegebene Werte
-pub fn substitute_for_variable(expr: Expr, variables: &HashMap<String, Expr>) -> Expr {
-    match expr {
-        Expr::Var(var) => {
-            if let Some(value) = variables.get(&var) {
-                value.clone()
-            } else {
-                panic!("Variable {} nicht gefunden", var);
-            }
-        }
-        Expr::BinaryOp(op, left, right) => {
-            Expr::BinaryOp(
-                op,
-                Box::new(substitute_for_variable(*left, variables)),
-                Box::new(substitute_for_variable(*right, variables)),
-            )
-        }
-        _ => expr,
-    }
-}
-
 // Erstellt eine Wertetabelle für einen Ausdruck
 pub fn values_table(expr: Expr) -> (Vec<Expr>, Vec<Expr>) {
     // TODO: Iteration über den Ausdruck hinzufügen, um alle Variablen zu finden
@@ -34,14 +13,16 @@ pub fn values_table(expr: Expr) -> (Vec<Expr>, Vec<Expr>) {
     let mut y_values: Vec<Expr> = Vec::new();
     // Entschuldigung für diesen Code
     let mut i = -5.0;
+    let compiled = compile(&expr, &["x".to_string()]);
     while i <= 5.0 {
-        let mut variables = HashMap::new();
-        variables.insert("x".to_string(), Expr::Number(i));
-
-        let simplified_expr = simplify(substitute_for_variable(expr.clone(), &variables), false);
-
-        x_values.push(Expr::Number(i));
-        y_values.push(simplified_expr);
+        // Punkte in Definitionslücken und direkt an Polstellen werden ausgelassen,
+        // damit sie die Skalierung der y-Achse nicht bestimmen
+        let enclosure = evaluate_interval(&expr, "x", Interval::new(i - 0.005, i + 0.005));
+        let near_pole = enclosure.is_ok_and(|enclosure| !enclosure.is_bounded());
+        if let Some(Ok(y)) = compiled.as_ref().ok().filter(|_| !near_pole).map(|f| f.call(&[i])) {
+            x_values.push(Expr::Number(i));
+            y_values.push(Expr::Number(y));
+        }
 
         i += 0.01;
     }
@@ -0,0 +1,560 @@
+use crate::differentiate::depends_on;
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+use crate::simplify::simplify;
+
+// Koeffizienten, deren Betrag kleiner ist, gelten als 0
+const EPSILON: f64 = 1e-9;
+
+/// Höchster Grad, bis zu dem Potenzen ausmultipliziert werden, damit Eingaben wie `x^1e9` nicht hängen.
+pub const MAX_DEGREE: usize = 1000;
+
+/// Eine komplexe Zahl, wie sie bei der Nullstellensuche von Polynomen benötigt wird.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    pub fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+
+    pub fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+
+    pub fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+
+    pub fn div(self, other: Complex) -> Complex {
+        let denominator = other.re * other.re + other.im * other.im;
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+
+    pub fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+/// Ein Polynom in einer Variablen mit reellen Koeffizienten.
+/// Die Koeffizienten sind aufsteigend nach Grad sortiert: `coeffs[i]` gehört zu `x^i`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    pub coeffs: Vec<f64>,
+}
+
+impl Polynomial {
+    // Erstellt ein Polynom und entfernt führende Nullkoeffizienten
+    pub fn new(coeffs: Vec<f64>) -> Polynomial {
+        let mut polynomial = Polynomial { coeffs };
+        polynomial.trim();
+        polynomial
+    }
+
+    pub fn zero() -> Polynomial {
+        Polynomial { coeffs: vec![] }
+    }
+
+    pub fn constant(c: f64) -> Polynomial {
+        Polynomial::new(vec![c])
+    }
+
+    // Erstellt das Monom c * x^n
+    pub fn monomial(c: f64, n: usize) -> Polynomial {
+        let mut coeffs = vec![0.0; n + 1];
+        coeffs[n] = c;
+        Polynomial::new(coeffs)
+    }
+
+    fn trim(&mut self) {
+        while let Some(c) = self.coeffs.last() {
+            if c.abs() < EPSILON {
+                self.coeffs.pop();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    // Das Nullpolynom hat hier den Grad 0
+    pub fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+
+    pub fn leading_coefficient(&self) -> f64 {
+        self.coeffs.last().copied().unwrap_or(0.0)
+    }
+
+    // Gibt den Koeffizienten von x^i zurück
+    pub fn coefficient(&self, i: usize) -> f64 {
+        self.coeffs.get(i).copied().unwrap_or(0.0)
+    }
+
+    pub fn add(&self, other: &Polynomial) -> Polynomial {
+        let n = self.coeffs.len().max(other.coeffs.len());
+        Polynomial::new((0..n).map(|i| self.coefficient(i) + other.coefficient(i)).collect())
+    }
+
+    pub fn sub(&self, other: &Polynomial) -> Polynomial {
+        self.add(&other.scale(-1.0))
+    }
+
+    pub fn scale(&self, c: f64) -> Polynomial {
+        Polynomial::new(self.coeffs.iter().map(|a| a * c).collect())
+    }
+
+    pub fn mul(&self, other: &Polynomial) -> Polynomial {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero();
+        }
+        let mut coeffs = vec![0.0; self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                coeffs[i + j] += a * b;
+            }
+        }
+        Polynomial::new(coeffs)
+    }
+
+    pub fn pow(&self, n: usize) -> Polynomial {
+        let mut result = Polynomial::constant(1.0);
+        for _ in 0..n {
+            result = result.mul(self);
+        }
+        result
+    }
+
+    /// Polynomdivision mit Rest: Gibt `(q, r)` mit `self = q * divisor + r` und `deg r < deg divisor` zurück.
+    pub fn div_rem(&self, divisor: &Polynomial) -> Result<(Polynomial, Polynomial), String> {
+        if divisor.is_zero() {
+            return Err("Error: Division by zero polynomial".to_string());
+        }
+        let mut remainder = self.coeffs.clone();
+        let divisor_degree = divisor.degree();
+        if self.is_zero() || self.degree() < divisor_degree {
+            return Ok((Polynomial::zero(), self.clone()));
+        }
+        let mut quotient = vec![0.0; self.degree() - divisor_degree + 1];
+        for i in (0..quotient.len()).rev() {
+            let c = remainder[i + divisor_degree] / divisor.leading_coefficient();
+            quotient[i] = c;
+            for (j, d) in divisor.coeffs.iter().enumerate() {
+                remainder[i + j] -= c * d;
+            }
+        }
+        remainder.truncate(divisor_degree);
+        Ok((Polynomial::new(quotient), Polynomial::new(remainder)))
+    }
+
+    pub fn derivative(&self) -> Polynomial {
+        Polynomial::new(self.coeffs.iter().enumerate().skip(1).map(|(i, c)| c * i as f64).collect())
+    }
+
+    // Quotient einer Division, die aufgehen soll; ein Rest aus Rundungsfehlern wird verworfen
+    pub fn exact_div(&self, divisor: &Polynomial) -> Polynomial {
+        self.div_rem(divisor).map(|(quotient, _)| quotient).unwrap_or_else(|_| Polynomial::zero())
+    }
+
+    /// Erweiterter euklidischer Algorithmus: Gibt `(g, s, t)` mit `s * self + t * other = g` zurück,
+    /// wobei `g` der normierte größte gemeinsame Teiler ist. Bei Gleitkommakoeffizienten ist das nur für
+    /// Polynome mit einfachen Koeffizienten verlässlich; exakt rechnet `ExactPolynomial`.
+    pub fn extended_gcd(&self, other: &Polynomial) -> (Polynomial, Polynomial, Polynomial) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut s0, mut s1) = (Polynomial::constant(1.0), Polynomial::zero());
+        let (mut t0, mut t1) = (Polynomial::zero(), Polynomial::constant(1.0));
+        while !r1.is_zero() {
+            let Ok((quotient, remainder)) = r0.div_rem(&r1) else {
+                break;
+            };
+            (r0, r1) = (r1, remainder);
+            (s0, s1) = (s1.clone(), s0.sub(&quotient.mul(&s1)));
+            (t0, t1) = (t1.clone(), t0.sub(&quotient.mul(&t1)));
+        }
+        let lc = r0.leading_coefficient();
+        if lc == 0.0 {
+            return (r0, s0, t0);
+        }
+        (r0.scale(1.0 / lc), s0.scale(1.0 / lc), t0.scale(1.0 / lc))
+    }
+
+    pub fn evaluate_complex(&self, z: Complex) -> Complex {
+        self.coeffs.iter().rev().fold(Complex::new(0.0, 0.0), |acc, c| acc.mul(z).add(Complex::new(*c, 0.0)))
+    }
+
+    /// Bestimmt alle komplexen Nullstellen (mit Vielfachheit) mit dem Aberth-Ehrlich-Verfahren.
+    pub fn roots(&self) -> Vec<Complex> {
+        let n = self.degree();
+        if self.is_zero() || n == 0 {
+            return vec![];
+        }
+        let derivative = self.derivative();
+        // Startwerte auf einem Kreis mit dem Radius der Cauchy-Schranke
+        let radius = 1.0 + self.coeffs[..n].iter().map(|c| (c / self.leading_coefficient()).abs()).fold(0.0, f64::max);
+        let mut roots: Vec<Complex> = (0..n)
+            .map(|k| {
+                let angle = 2.0 * std::f64::consts::PI * k as f64 / n as f64 + 0.4;
+                Complex::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+
+        for _ in 0..1000 {
+            let mut max_step: f64 = 0.0;
+            for i in 0..n {
+                let value = self.evaluate_complex(roots[i]);
+                if value.abs() == 0.0 {
+                    continue;
+                }
+                let ratio = value.div(derivative.evaluate_complex(roots[i]));
+                let mut sum = Complex::new(0.0, 0.0);
+                for (j, other) in roots.iter().enumerate() {
+                    if i != j {
+                        sum = sum.add(Complex::new(1.0, 0.0).div(roots[i].sub(*other)));
+                    }
+                }
+                let step = ratio.div(Complex::new(1.0, 0.0).sub(ratio.mul(sum)));
+                if step.re.is_finite() && step.im.is_finite() {
+                    roots[i] = roots[i].sub(step);
+                    max_step = max_step.max(step.abs() / (1.0 + roots[i].abs()));
+                }
+            }
+            if max_step < 1e-15 {
+                break;
+            }
+        }
+        roots
+    }
+
+    /// Zerlegt das Polynom über den reellen Zahlen in normierte lineare und irreduzible quadratische Faktoren.
+    /// Gibt die Faktoren zusammen mit ihrer Vielfachheit zurück; der Leitkoeffizient ist nicht enthalten.
+    pub fn real_factors(&self) -> Vec<(Polynomial, usize)> {
+        let mut roots = self.roots();
+        let mut factors = vec![];
+        while let Some(root) = roots.pop() {
+            // Mehrfache Nullstellen werden nur ungenau gefunden und liegen deshalb dicht beieinander
+            let tolerance = 1e-4 * (1.0 + root.abs());
+            let mut cluster = vec![root];
+            roots.retain(|other| {
+                if other.sub(root).abs() < tolerance {
+                    cluster.push(*other);
+                    false
+                } else {
+                    true
+                }
+            });
+            let multiplicity = cluster.len();
+            let mut center = cluster.iter().fold(Complex::new(0.0, 0.0), |acc, z| acc.add(*z));
+            center = Complex::new(center.re / multiplicity as f64, center.im / multiplicity as f64);
+
+            // Eine m-fache Nullstelle ist eine einfache Nullstelle der (m-1)-ten Ableitung
+            let mut refine = self.clone();
+            for _ in 1..multiplicity {
+                refine = refine.derivative();
+            }
+            let refine_derivative = refine.derivative();
+            for _ in 0..50 {
+                let step = refine.evaluate_complex(center).div(refine_derivative.evaluate_complex(center));
+                if !(step.re.is_finite() && step.im.is_finite()) {
+                    break;
+                }
+                center = center.sub(step);
+                if step.abs() < 1e-16 * (1.0 + center.abs()) {
+                    break;
+                }
+            }
+
+            let re = snap(center.re);
+            let im = snap(center.im);
+            if im.abs() < 1e-7 * (1.0 + re.abs()) {
+                factors.push((Polynomial::new(vec![-re, 1.0]), multiplicity));
+            } else if im > 0.0 {
+                // Konjugiert komplexe Paare ergeben einen quadratischen Faktor; nur einer der beiden wird gezählt
+                factors.push((Polynomial::new(vec![snap(re * re + im * im), snap(-2.0 * re), 1.0]), multiplicity));
+            }
+        }
+        factors.reverse();
+        factors
+    }
+
+    /// Wandelt das Polynom in einen Ausdruck in der Variablen `var` um, absteigend nach Grad.
+    pub fn to_expr(&self, var: String) -> Expr {
+        let mut result: Option<Expr> = None;
+        for (i, c) in self.coeffs.iter().enumerate().rev() {
+            if c.abs() < EPSILON {
+                continue;
+            }
+            let power = match i {
+                0 => None,
+                1 => Some(Expr::Var(var.clone())),
+                _ => Some(Expr::BinaryOp(
+                    BinaryOpKind::Pow,
+                    Box::new(Expr::Var(var.clone())),
+                    Box::new(Expr::Number(i as f64)),
+                )),
+            };
+            let magnitude = c.abs();
+            let term = match power {
+                None => Expr::Number(magnitude),
+                Some(power) if magnitude == 1.0 => power,
+                Some(power) => Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(magnitude)), Box::new(power)),
+            };
+            result = Some(match result {
+                None if *c < 0.0 => Expr::UnaryOp(UnaryOpKind::Neg, Box::new(term)),
+                None => term,
+                Some(acc) if *c < 0.0 => Expr::BinaryOp(BinaryOpKind::Sub, Box::new(acc), Box::new(term)),
+                Some(acc) => Expr::BinaryOp(BinaryOpKind::Add, Box::new(acc), Box::new(term)),
+            });
+        }
+        result.unwrap_or(Expr::Number(0.0))
+    }
+}
+
+/// Zerlegt ein Polynom in `var` in seine Koeffizienten, beginnend beim konstanten Glied.
+/// Die Koeffizienten dürfen andere Variablen enthalten, z.B. `a x^2 - x/2` → `[0, -0.5, a]`.
+/// Produkte und ganzzahlige Potenzen von Summen werden dabei ausmultipliziert.
+pub fn polynomial_coefficients(expr: &Expr, var: &str) -> Result<Vec<Expr>, String> {
+    let coefficients = coefficients(expr, var)?;
+    let mut coefficients: Vec<Expr> = coefficients.into_iter().map(|c| simplify(c, false)).collect();
+    while coefficients.len() > 1 && coefficients.last() == Some(&Expr::Number(0.0)) {
+        coefficients.pop();
+    }
+    Ok(coefficients)
+}
+
+fn coefficients(expr: &Expr, var: &str) -> Result<Vec<Expr>, String> {
+    if !depends_on(expr, var) {
+        return Ok(vec![expr.clone()]);
+    }
+    let binary = |op: BinaryOpKind, a: &Expr, b: &Expr| Expr::BinaryOp(op, Box::new(a.clone()), Box::new(b.clone()));
+    // Koeffizientenweise Verknüpfung zweier Listen, fehlende Koeffizienten sind 0
+    let combine = |left: Vec<Expr>, right: Vec<Expr>, op: BinaryOpKind| -> Vec<Expr> {
+        (0..left.len().max(right.len()))
+            .map(|i| {
+                let zero = Expr::Number(0.0);
+                binary(op, left.get(i).unwrap_or(&zero), right.get(i).unwrap_or(&zero))
+            })
+            .collect()
+    };
+    // Faltung der Koeffizienten beim Multiplizieren
+    let multiply = |left: &[Expr], right: &[Expr]| -> Vec<Expr> {
+        let mut result = vec![Expr::Number(0.0); left.len() + right.len() - 1];
+        for (i, a) in left.iter().enumerate() {
+            for (j, b) in right.iter().enumerate() {
+                result[i + j] = simplify(binary(BinaryOpKind::Add, &result[i + j], &binary(BinaryOpKind::Mul, a, b)), false);
+            }
+        }
+        result
+    };
+    match expr {
+        Expr::Var(_) => Ok(vec![Expr::Number(0.0), Expr::Number(1.0)]),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => Ok(coefficients(inner, var)?
+            .into_iter()
+            .map(|c| Expr::UnaryOp(UnaryOpKind::Neg, Box::new(c)))
+            .collect()),
+        Expr::BinaryOp(op @ (BinaryOpKind::Add | BinaryOpKind::Sub), left, right) => {
+            Ok(combine(coefficients(left, var)?, coefficients(right, var)?, *op))
+        }
+        Expr::BinaryOp(BinaryOpKind::Mul, left, right) => Ok(multiply(&coefficients(left, var)?, &coefficients(right, var)?)),
+        // Division nur durch Ausdrücke, die nicht von `var` abhängen
+        Expr::BinaryOp(BinaryOpKind::Div, left, right) if !depends_on(right, var) => {
+            Ok(coefficients(left, var)?.iter().map(|c| binary(BinaryOpKind::Div, c, right)).collect())
+        }
+        Expr::BinaryOp(BinaryOpKind::Pow, base, exponent) => match **exponent {
+            Expr::Number(n) if n >= 0.0 && n.fract() == 0.0 => {
+                let base = coefficients(base, var)?;
+                if base.len().saturating_sub(1).max(1) as f64 * n > MAX_DEGREE as f64 {
+                    return Err(format!("Error: Degree exceeds the maximum of {}", MAX_DEGREE));
+                }
+                Ok((0..n as usize).fold(vec![Expr::Number(1.0)], |acc, _| multiply(&acc, &base)))
+            }
+            _ => Err(format!("Error: Unsupported exponent in polynomial: {:?}", exponent)),
+        },
+        _ => Err(format!("Error: Not a polynomial in {}: {:?}", var, expr)),
+    }
+}
+
+/// Wandelt einen Ausdruck in eine gebrochenrationale Funktion `(Zähler, Nenner)` in `var` um.
+pub fn rational_function_from_expr(expr: Expr, var: String) -> Result<(Polynomial, Polynomial), String> {
+    match expr {
+        Expr::Number(a) => Ok((Polynomial::constant(a), Polynomial::constant(1.0))),
+        Expr::Var(v) => {
+            if v == var {
+                Ok((Polynomial::monomial(1.0, 1), Polynomial::constant(1.0)))
+            } else {
+                Err(format!("Error: Unexpected variable in rational function: {}", v))
+            }
+        }
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => {
+            let (numerator, denominator) = rational_function_from_expr(*inner, var)?;
+            Ok((numerator.scale(-1.0), denominator))
+        }
+        Expr::BinaryOp(op, left, right) => {
+            let (left_num, left_den) = rational_function_from_expr(*left, var.clone())?;
+            match op {
+                BinaryOpKind::Pow => {
+                    // Nur ganzzahlige Exponenten ergeben wieder eine gebrochenrationale Funktion
+                    let exponent = match *right {
+                        Expr::Number(n) => n,
+                        Expr::UnaryOp(UnaryOpKind::Neg, ref inner) if matches!(**inner, Expr::Number(_)) => {
+                            if let Expr::Number(n) = **inner { -n } else { unreachable!() }
+                        }
+                        _ => f64::NAN,
+                    };
+                    let degree = left_num.degree().max(left_den.degree()).max(1) as f64;
+                    if exponent.fract() != 0.0 {
+                        Err(format!("Error: Unsupported exponent in rational function: {:?}", right))
+                    } else if degree * exponent.abs() > MAX_DEGREE as f64 {
+                        Err(format!("Error: Degree exceeds the maximum of {}", MAX_DEGREE))
+                    } else if exponent >= 0.0 {
+                        Ok((left_num.pow(exponent as usize), left_den.pow(exponent as usize)))
+                    } else if left_num.is_zero() {
+                        Err("Error: Division by zero in rational function".to_string())
+                    } else {
+                        Ok((left_den.pow(-exponent as usize), left_num.pow(-exponent as usize)))
+                    }
+                }
+                _ => {
+                    let (right_num, right_den) = rational_function_from_expr(*right, var)?;
+                    match op {
+                        BinaryOpKind::Add => Ok((
+                            left_num.mul(&right_den).add(&right_num.mul(&left_den)),
+                            left_den.mul(&right_den),
+                        )),
+                        BinaryOpKind::Sub => Ok((
+                            left_num.mul(&right_den).sub(&right_num.mul(&left_den)),
+                            left_den.mul(&right_den),
+                        )),
+                        BinaryOpKind::Mul => Ok((left_num.mul(&right_num), left_den.mul(&right_den))),
+                        BinaryOpKind::Div => {
+                            if right_num.is_zero() {
+                                return Err("Error: Division by zero in rational function".to_string());
+                            }
+                            Ok((left_num.mul(&right_den), left_den.mul(&right_num)))
+                        }
+                        BinaryOpKind::Pow => unreachable!(),
+                    }
+                }
+            }
+        }
+        Expr::Call(func, args) => match (*func, args.len()) {
+            (Expr::Var(name), 1) if name == "id" => rational_function_from_expr(args[0].clone(), var),
+            (func, _) => Err(format!("Error: Unsupported function in rational function: {:?}", func)),
+        },
+        Expr::Derivative(func, _) => Err(format!("Error: Unsupported function in rational function: {:?}", func)),
+        Expr::Matrix(_) => Err("Error: A matrix is not a rational function".to_string()),
+        Expr::Equation(_, _) => Err("Error: An equation is not a rational function".to_string()),
+    }
+}
+
+/// Zahlen, die einem einfachen Bruch entsprechen, werden als p/q statt als Dezimalzahl dargestellt.
+pub fn exact_number(n: f64) -> Expr {
+    match rational_approximation(n.abs(), 1000) {
+        Some((p, q)) if q > 1 => {
+            let fraction = Expr::BinaryOp(BinaryOpKind::Div, Box::new(Expr::Number(p as f64)), Box::new(Expr::Number(q as f64)));
+            if n < 0.0 {
+                Expr::UnaryOp(UnaryOpKind::Neg, Box::new(fraction))
+            } else {
+                fraction
+            }
+        }
+        _ => Expr::Number(n),
+    }
+}
+
+/// Sucht einen Bruch `p/q` mit `q <= max_denominator`, der `x` bis auf Rundungsfehler darstellt.
+pub fn rational_approximation(x: f64, max_denominator: i64) -> Option<(i64, i64)> {
+    if !x.is_finite() {
+        return None;
+    }
+    // Kettenbruchentwicklung mit den Näherungsbrüchen h/k
+    let (mut h0, mut h1) = (0i64, 1i64);
+    let (mut k0, mut k1) = (1i64, 0i64);
+    let mut rest = x;
+    for _ in 0..64 {
+        let a = rest.floor();
+        if a.abs() > 1e15 {
+            return None;
+        }
+        let a = a as i64;
+        let h2 = a.checked_mul(h1)?.checked_add(h0)?;
+        let k2 = a.checked_mul(k1)?.checked_add(k0)?;
+        if k2 > max_denominator {
+            return None;
+        }
+        (h0, h1, k0, k1) = (h1, h2, k1, k2);
+        if (x - h1 as f64 / k1 as f64).abs() <= 1e-10 * x.abs().max(1.0) {
+            return Some((h1, k1));
+        }
+        let fraction = rest - a as f64;
+        if fraction == 0.0 {
+            return None;
+        }
+        rest = 1.0 / fraction;
+    }
+    None
+}
+
+// Rundet Zahlen, die sehr nahe an einem einfachen Bruch liegen, auf diesen Bruch
+pub fn snap(x: f64) -> f64 {
+    match rational_approximation(x, 1000) {
+        Some((p, q)) => p as f64 / q as f64,
+        None => x,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_div_rem() {
+        // (x^3 - 1) / (x - 1) = x^2 + x + 1
+        let (q, r) = Polynomial::new(vec![-1.0, 0.0, 0.0, 1.0]).div_rem(&Polynomial::new(vec![-1.0, 1.0])).unwrap();
+        assert_eq!(q, Polynomial::new(vec![1.0, 1.0, 1.0]));
+        assert!(r.is_zero());
+    }
+
+    #[test]
+    fn test_real_factors() {
+        // (x - 1)^2 (x^2 + 1)
+        let p = Polynomial::new(vec![-1.0, 1.0]).pow(2).mul(&Polynomial::new(vec![1.0, 0.0, 1.0]));
+        let factors = p.real_factors();
+        assert_eq!(factors.len(), 2);
+        assert!(factors.contains(&(Polynomial::new(vec![-1.0, 1.0]), 2)));
+        assert!(factors.contains(&(Polynomial::new(vec![1.0, 0.0, 1.0]), 1)));
+    }
+
+    #[test]
+    fn test_degree_limit() {
+        // x^1e9 würde nie fertig ausmultipliziert
+        let power = |n: f64| Expr::BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Var("x".to_string())), Box::new(Expr::Number(n)));
+        assert!(rational_function_from_expr(power(1e9), "x".to_string()).is_err());
+        assert!(rational_function_from_expr(power(-1e9), "x".to_string()).is_err());
+        let (numerator, _) = rational_function_from_expr(power(12.0), "x".to_string()).unwrap();
+        assert_eq!(numerator.degree(), 12);
+        assert!(polynomial_coefficients(&power(1e9), "x").is_err());
+        assert_eq!(polynomial_coefficients(&power(12.0), "x").map(|c| c.len()), Ok(13));
+    }
+
+    #[test]
+    fn test_rational_approximation() {
+        assert_eq!(rational_approximation(1.0 / 3.0, 1000), Some((1, 3)));
+        assert_eq!(rational_approximation(-0.75, 1000), Some((-3, 4)));
+        assert_eq!(rational_approximation(std::f64::consts::PI, 1000), None);
+    }
+}
@@ -0,0 +1,22 @@
+/// Einfacher deterministischer Zufallszahlengenerator (xorshift64), damit Ergebnisse reproduzierbar sind.
+pub struct Xorshift(u64);
+
+impl Xorshift {
+    pub fn new(seed: u64) -> Xorshift {
+        // Der Zustand darf nie 0 werden
+        Xorshift(seed.max(1))
+    }
+
+    /// Gleichverteilt in [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Gleichverteilt in [lower, upper).
+    pub fn uniform(&mut self, lower: f64, upper: f64) -> f64 {
+        lower + (upper - lower) * self.next_f64()
+    }
+}
@@ -0,0 +1,461 @@
+use crate::polynomial::{rational_approximation, Polynomial};
+
+// Größter Nenner, bis zu dem Gleitkommazahlen als Bruch gelesen werden
+const MAX_DENOMINATOR: i64 = 1_000_000_000_000;
+
+/// Koeffizienten, mit denen exakt gerechnet werden kann. Jede Operation gibt `None` zurück, wenn ein
+/// Zwischenergebnis nicht mehr darstellbar ist oder durch 0 geteilt wird.
+pub trait Field: Copy + PartialEq + std::fmt::Debug {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn integer(n: i128) -> Self;
+    fn add(self, other: Self) -> Option<Self>;
+    fn sub(self, other: Self) -> Option<Self>;
+    fn mul(self, other: Self) -> Option<Self>;
+    fn div(self, other: Self) -> Option<Self>;
+
+    fn is_zero(self) -> bool {
+        self == Self::zero()
+    }
+
+    fn pow(self, n: usize) -> Option<Self> {
+        (0..n).try_fold(Self::one(), |acc, _| acc.mul(self))
+    }
+}
+
+/// Ein gekürzter Bruch `num/den` mit positivem Nenner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a as i128
+}
+
+impl Rational {
+    pub fn new(num: i128, den: i128) -> Option<Rational> {
+        if den == 0 {
+            return None;
+        }
+        let g = gcd(num, den).max(1);
+        let (num, den) = (num / g, den / g);
+        if den < 0 {
+            Some(Rational { num: num.checked_neg()?, den: den.checked_neg()? })
+        } else {
+            Some(Rational { num, den })
+        }
+    }
+
+    /// Der einfachste Bruch, der `x` bis auf Rundungsfehler darstellt, z.B. 1/3 für 0.333…
+    pub fn approximate(x: f64) -> Option<Rational> {
+        let (p, q) = rational_approximation(x.abs(), MAX_DENOMINATOR)?;
+        let p = if x < 0.0 { -p } else { p };
+        Rational::new(p as i128, q as i128)
+    }
+
+    /// Liest eine Gleitkommazahl als den einfachsten Bruch, der sie bis auf wenige Einheiten der letzten
+    /// Stelle darstellt, z.B. 1.0000001 als 10000001/10000000. Mit diesem Bruch zu rechnen ist so genau
+    /// wie die Gleitkommazahl selbst, auch wenn sie eigentlich irrational ist.
+    pub fn from_f64(x: f64) -> Option<Rational> {
+        if !x.is_finite() {
+            return None;
+        }
+        // Kettenbruchentwicklung mit den Näherungsbrüchen h/k wie bei `rational_approximation`
+        let (mut h0, mut h1) = (0i128, 1i128);
+        let (mut k0, mut k1) = (1i128, 0i128);
+        let mut rest = x.abs();
+        loop {
+            let a = rest.floor();
+            if a > 1e30 {
+                return None;
+            }
+            (h0, h1) = (h1, (a as i128).checked_mul(h1)?.checked_add(h0)?);
+            (k0, k1) = (k1, (a as i128).checked_mul(k1)?.checked_add(k0)?);
+            if (h1 as f64 / k1 as f64 - x.abs()).abs() <= 4.0 * f64::EPSILON * x.abs() {
+                return Rational::new(if x < 0.0 { -h1 } else { h1 }, k1);
+            }
+            rest = 1.0 / (rest - a);
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.num < 0
+    }
+}
+
+impl Field for Rational {
+    fn zero() -> Rational {
+        Rational { num: 0, den: 1 }
+    }
+
+    fn one() -> Rational {
+        Rational { num: 1, den: 1 }
+    }
+
+    fn integer(n: i128) -> Rational {
+        Rational { num: n, den: 1 }
+    }
+
+    fn add(self, other: Rational) -> Option<Rational> {
+        let g = gcd(self.den, other.den);
+        let num = self.num.checked_mul(other.den / g)?.checked_add(other.num.checked_mul(self.den / g)?)?;
+        Rational::new(num, (self.den / g).checked_mul(other.den)?)
+    }
+
+    fn sub(self, other: Rational) -> Option<Rational> {
+        self.add(Rational { num: other.num.checked_neg()?, den: other.den })
+    }
+
+    fn mul(self, other: Rational) -> Option<Rational> {
+        // Vor dem Multiplizieren über Kreuz kürzen, damit Zwischenergebnisse klein bleiben
+        let (g1, g2) = (gcd(self.num, other.den).max(1), gcd(other.num, self.den).max(1));
+        let num = (self.num / g1).checked_mul(other.num / g2)?;
+        Rational::new(num, (self.den / g2).checked_mul(other.den / g1)?)
+    }
+
+    fn div(self, other: Rational) -> Option<Rational> {
+        self.mul(Rational::new(other.den, other.num)?)
+    }
+}
+
+/// Eine komplexe Zahl mit rationalem Real- und Imaginärteil, z.B. ein Residuum ±i/2 bei 1/(x²+1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gaussian {
+    pub re: Rational,
+    pub im: Rational,
+}
+
+impl Gaussian {
+    pub fn new(re: Rational, im: Rational) -> Gaussian {
+        Gaussian { re, im }
+    }
+
+    pub fn conjugate(self) -> Option<Gaussian> {
+        Some(Gaussian::new(self.re, Rational::zero().sub(self.im)?))
+    }
+}
+
+impl Field for Gaussian {
+    fn zero() -> Gaussian {
+        Gaussian::new(Rational::zero(), Rational::zero())
+    }
+
+    fn one() -> Gaussian {
+        Gaussian::new(Rational::one(), Rational::zero())
+    }
+
+    fn integer(n: i128) -> Gaussian {
+        Gaussian::new(Rational::integer(n), Rational::zero())
+    }
+
+    fn add(self, other: Gaussian) -> Option<Gaussian> {
+        Some(Gaussian::new(self.re.add(other.re)?, self.im.add(other.im)?))
+    }
+
+    fn sub(self, other: Gaussian) -> Option<Gaussian> {
+        Some(Gaussian::new(self.re.sub(other.re)?, self.im.sub(other.im)?))
+    }
+
+    fn mul(self, other: Gaussian) -> Option<Gaussian> {
+        let re = self.re.mul(other.re)?.sub(self.im.mul(other.im)?)?;
+        let im = self.re.mul(other.im)?.add(self.im.mul(other.re)?)?;
+        Some(Gaussian::new(re, im))
+    }
+
+    fn div(self, other: Gaussian) -> Option<Gaussian> {
+        let norm = other.re.mul(other.re)?.add(other.im.mul(other.im)?)?;
+        let product = self.mul(other.conjugate()?)?;
+        Some(Gaussian::new(product.re.div(norm)?, product.im.div(norm)?))
+    }
+}
+
+/// Ein Polynom mit exakten Koeffizienten, aufsteigend nach Grad sortiert wie bei `Polynomial`.
+/// Anders als dort gilt ein Koeffizient nur dann als 0, wenn er exakt 0 ist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExactPolynomial<F> {
+    pub coeffs: Vec<F>,
+}
+
+impl<F: Field> ExactPolynomial<F> {
+    // Erstellt ein Polynom und entfernt führende Nullkoeffizienten
+    pub fn new(mut coeffs: Vec<F>) -> ExactPolynomial<F> {
+        while coeffs.last().is_some_and(|c| c.is_zero()) {
+            coeffs.pop();
+        }
+        ExactPolynomial { coeffs }
+    }
+
+    pub fn zero() -> ExactPolynomial<F> {
+        ExactPolynomial { coeffs: vec![] }
+    }
+
+    pub fn constant(c: F) -> ExactPolynomial<F> {
+        ExactPolynomial::new(vec![c])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+
+    pub fn leading_coefficient(&self) -> F {
+        self.coeffs.last().copied().unwrap_or(F::zero())
+    }
+
+    pub fn add(&self, other: &ExactPolynomial<F>) -> Option<ExactPolynomial<F>> {
+        let coeffs = (0..self.coeffs.len().max(other.coeffs.len()))
+            .map(|i| self.coefficient(i).add(other.coefficient(i)))
+            .collect::<Option<Vec<F>>>()?;
+        Some(ExactPolynomial::new(coeffs))
+    }
+
+    pub fn sub(&self, other: &ExactPolynomial<F>) -> Option<ExactPolynomial<F>> {
+        self.add(&other.scale(F::zero().sub(F::one())?)?)
+    }
+
+    pub fn scale(&self, c: F) -> Option<ExactPolynomial<F>> {
+        Some(ExactPolynomial::new(self.coeffs.iter().map(|a| a.mul(c)).collect::<Option<Vec<F>>>()?))
+    }
+
+    pub fn mul(&self, other: &ExactPolynomial<F>) -> Option<ExactPolynomial<F>> {
+        if self.is_zero() || other.is_zero() {
+            return Some(ExactPolynomial::zero());
+        }
+        let mut coeffs = vec![F::zero(); self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                coeffs[i + j] = coeffs[i + j].add(a.mul(*b)?)?;
+            }
+        }
+        Some(ExactPolynomial::new(coeffs))
+    }
+
+    pub fn pow(&self, n: usize) -> Option<ExactPolynomial<F>> {
+        (0..n).try_fold(ExactPolynomial::constant(F::one()), |acc, _| acc.mul(self))
+    }
+
+    /// Polynomdivision mit Rest; `None` bei Division durch das Nullpolynom.
+    pub fn div_rem(&self, divisor: &ExactPolynomial<F>) -> Option<(ExactPolynomial<F>, ExactPolynomial<F>)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let mut remainder = self.clone();
+        let mut quotient = vec![F::zero(); self.coeffs.len().saturating_sub(divisor.degree())];
+        while !remainder.is_zero() && remainder.degree() >= divisor.degree() {
+            let shift = remainder.degree() - divisor.degree();
+            let factor = remainder.leading_coefficient().div(divisor.leading_coefficient())?;
+            quotient[shift] = factor;
+            let mut term = vec![F::zero(); shift];
+            term.push(factor);
+            remainder = remainder.sub(&divisor.mul(&ExactPolynomial::new(term))?)?;
+        }
+        Some((ExactPolynomial::new(quotient), remainder))
+    }
+
+    // Quotient einer Division, die aufgehen muss
+    pub fn exact_div(&self, divisor: &ExactPolynomial<F>) -> Option<ExactPolynomial<F>> {
+        let (quotient, remainder) = self.div_rem(divisor)?;
+        remainder.is_zero().then_some(quotient)
+    }
+
+    pub fn derivative(&self) -> Option<ExactPolynomial<F>> {
+        let coeffs = self.coeffs.iter().enumerate().skip(1).map(|(i, c)| c.mul(F::integer(i as i128))).collect::<Option<Vec<F>>>()?;
+        Some(ExactPolynomial::new(coeffs))
+    }
+
+    pub fn integral(&self) -> Option<ExactPolynomial<F>> {
+        let mut coeffs = vec![F::zero()];
+        for (i, c) in self.coeffs.iter().enumerate() {
+            coeffs.push(c.div(F::integer(i as i128 + 1))?);
+        }
+        Some(ExactPolynomial::new(coeffs))
+    }
+
+    pub fn monic(&self) -> Option<ExactPolynomial<F>> {
+        if self.is_zero() {
+            return Some(self.clone());
+        }
+        self.scale(F::one().div(self.leading_coefficient())?)
+    }
+
+    pub fn evaluate(&self, x: F) -> Option<F> {
+        self.coeffs.iter().rev().try_fold(F::zero(), |acc, c| acc.mul(x)?.add(*c))
+    }
+
+    /// Erweiterter euklidischer Algorithmus: Gibt `(g, s, t)` mit `s * self + t * other = g` zurück,
+    /// wobei `g` der normierte größte gemeinsame Teiler ist.
+    pub fn extended_gcd(&self, other: &ExactPolynomial<F>) -> Option<(ExactPolynomial<F>, ExactPolynomial<F>, ExactPolynomial<F>)> {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut s0, mut s1) = (ExactPolynomial::constant(F::one()), ExactPolynomial::zero());
+        let (mut t0, mut t1) = (ExactPolynomial::zero(), ExactPolynomial::constant(F::one()));
+        while !r1.is_zero() {
+            let (quotient, remainder) = r0.div_rem(&r1)?;
+            (r0, r1) = (r1, remainder);
+            (s0, s1) = (s1.clone(), s0.sub(&quotient.mul(&s1)?)?);
+            (t0, t1) = (t1.clone(), t0.sub(&quotient.mul(&t1)?)?);
+        }
+        if r0.is_zero() {
+            return Some((r0, s0, t0));
+        }
+        let inverse = F::one().div(r0.leading_coefficient())?;
+        Some((r0.scale(inverse)?, s0.scale(inverse)?, t0.scale(inverse)?))
+    }
+
+    pub fn gcd(&self, other: &ExactPolynomial<F>) -> Option<ExactPolynomial<F>> {
+        Some(self.extended_gcd(other)?.0)
+    }
+
+    /// Quadratfreie Zerlegung nach Yun: Gibt normierte, paarweise teilerfremde und quadratfreie
+    /// Polynome `f_1, ..., f_m` mit `self = lc * f_1 * f_2^2 * ... * f_m^m` zurück.
+    pub fn squarefree_factorization(&self) -> Option<Vec<ExactPolynomial<F>>> {
+        if self.is_zero() {
+            return Some(vec![]);
+        }
+        let monic = self.monic()?;
+        let derivative = monic.derivative()?;
+        let common = monic.gcd(&derivative)?;
+        let mut b = monic.exact_div(&common)?;
+        let mut d = derivative.exact_div(&common)?.sub(&b.derivative()?)?;
+        let mut factors = vec![];
+        while b.degree() > 0 {
+            let factor = b.gcd(&d)?;
+            b = b.exact_div(&factor)?;
+            d = d.exact_div(&factor)?.sub(&b.derivative()?)?;
+            factors.push(factor);
+        }
+        Some(factors)
+    }
+
+    /// Resultante über den euklidischen Algorithmus mit res(f, g) = (-1)^(mn) lc(g)^(m-k) res(g, f mod g),
+    /// wobei k der Grad von f mod g ist. Bei normiertem `self` ist sie das Produkt von `other` über alle
+    /// Nullstellen von `self`, unabhängig davon, ob der Grad von `other` für spezielle Werte absinkt.
+    pub fn resultant(&self, other: &ExactPolynomial<F>) -> Option<F> {
+        if self.is_zero() || other.is_zero() {
+            return Some(F::zero());
+        }
+        let (mut f, mut g) = (self.clone(), other.clone());
+        let mut result = F::one();
+        while g.degree() > 0 {
+            let (m, n) = (f.degree(), g.degree());
+            let remainder = f.div_rem(&g)?.1;
+            if remainder.is_zero() {
+                return Some(F::zero());
+            }
+            if m * n % 2 == 1 {
+                result = F::zero().sub(result)?;
+            }
+            result = result.mul(g.leading_coefficient().pow(m - remainder.degree())?)?;
+            (f, g) = (g, remainder);
+        }
+        result.mul(g.leading_coefficient().pow(f.degree())?)
+    }
+
+    /// Lagrange-Interpolation durch die Punkte `(x_k, y_k)` mit paarweise verschiedenen `x_k`.
+    pub fn interpolate(points: &[(F, F)]) -> Option<ExactPolynomial<F>> {
+        let mut result = ExactPolynomial::zero();
+        for (k, (xk, yk)) in points.iter().enumerate() {
+            let mut basis = ExactPolynomial::constant(*yk);
+            for (j, (xj, _)) in points.iter().enumerate() {
+                if j != k {
+                    let factor = ExactPolynomial::new(vec![F::zero().sub(*xj)?, F::one()]);
+                    basis = basis.mul(&factor)?.scale(F::one().div(xk.sub(*xj)?)?)?;
+                }
+            }
+            result = result.add(&basis)?;
+        }
+        Some(result)
+    }
+
+    fn coefficient(&self, i: usize) -> F {
+        self.coeffs.get(i).copied().unwrap_or(F::zero())
+    }
+}
+
+impl ExactPolynomial<Rational> {
+    /// Liest die Koeffizienten als Brüche, siehe `Rational::from_f64`; `None`, wenn einer irrational ist.
+    pub fn from_polynomial(polynomial: &Polynomial) -> Option<ExactPolynomial<Rational>> {
+        Some(ExactPolynomial::new(polynomial.coeffs.iter().map(|c| Rational::from_f64(*c)).collect::<Option<Vec<Rational>>>()?))
+    }
+
+    pub fn to_polynomial(&self) -> Polynomial {
+        Polynomial::new(self.coeffs.iter().map(|c| c.to_f64()).collect())
+    }
+
+    pub fn to_gaussian(&self) -> ExactPolynomial<Gaussian> {
+        ExactPolynomial::new(self.coeffs.iter().map(|c| Gaussian::new(*c, Rational::zero())).collect())
+    }
+}
+
+impl ExactPolynomial<Gaussian> {
+    /// Zerlegt das Polynom in Real- und Imaginärteil `p + i q`.
+    pub fn parts(&self) -> (ExactPolynomial<Rational>, ExactPolynomial<Rational>) {
+        (
+            ExactPolynomial::new(self.coeffs.iter().map(|c| c.re).collect()),
+            ExactPolynomial::new(self.coeffs.iter().map(|c| c.im).collect()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rational(num: i128, den: i128) -> Rational {
+        Rational::new(num, den).unwrap()
+    }
+
+    fn polynomial(coeffs: &[i128]) -> ExactPolynomial<Rational> {
+        ExactPolynomial::new(coeffs.iter().map(|c| Rational::integer(*c)).collect())
+    }
+
+    #[test]
+    fn test_rational_arithmetic() {
+        assert_eq!(rational(2, -4), rational(-1, 2));
+        assert_eq!(rational(1, 6).add(rational(1, 3)), Some(rational(1, 2)));
+        assert_eq!(rational(3, 4).div(rational(3, 8)), Some(Rational::integer(2)));
+        assert_eq!(rational(1, 2).div(Rational::zero()), None);
+        assert_eq!(Rational::integer(i128::MAX).add(Rational::one()), None);
+        assert_eq!(Rational::from_f64(1.0000001), Some(rational(10000001, 10000000)));
+        assert_eq!(Rational::from_f64(0.1), Some(rational(1, 10)));
+        assert_eq!(Rational::from_f64(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_squarefree_factorization() {
+        // 2 (x + 1) (x - 2)^3
+        let linear = polynomial(&[1, 1]);
+        let cubed = polynomial(&[-2, 1]);
+        let p = linear.mul(&cubed.pow(3).unwrap()).unwrap().scale(Rational::integer(2)).unwrap();
+        let factors = p.squarefree_factorization().unwrap();
+        assert_eq!(factors, vec![linear, polynomial(&[1]), cubed]);
+        // Nahe beieinanderliegende Nullstellen bleiben getrennt
+        let close = ExactPolynomial::new(vec![rational(-1, 1), Rational::one()])
+            .mul(&ExactPolynomial::new(vec![rational(-10000001, 10000000), Rational::one()]))
+            .unwrap();
+        assert_eq!(close.squarefree_factorization().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_resultant() {
+        // res(x^2 - 1, x - 3) = (1 - 3)(-1 - 3) = 8
+        assert_eq!(polynomial(&[-1, 0, 1]).resultant(&polynomial(&[-3, 1])), Some(Rational::integer(8)));
+        // Gemeinsame Nullstelle
+        assert_eq!(polynomial(&[-1, 0, 1]).resultant(&polynomial(&[1, 1])), Some(Rational::zero()));
+        // Interpolation durch (0, 1), (1, 2), (2, 5) ergibt x^2 + 1
+        let points = [(0, 1), (1, 2), (2, 5)].map(|(x, y)| (Rational::integer(x), Rational::integer(y)));
+        assert_eq!(ExactPolynomial::interpolate(&points), Some(polynomial(&[1, 0, 1])));
+    }
+}
@@ -8,6 +8,8 @@ pub fn render_latex(expr: &Expr) -> String {
         Expr::Var(v) => {
             if is_elementary_function(v) {
                 render_elementary_function(v)
+            } else if v == "pi" {
+                "\\pi".to_string()
             } else {
                 v.clone()
             }
@@ -28,11 +30,21 @@ pub fn render_latex(expr: &Expr) -> String {
             let f = render_latex(func);
             let args_tex = args.iter().map(|arg| render_latex(arg)).collect::<Vec<_>>().join(", ");
             if let Expr::Var(name) = &**func {
+                match (name.as_str(), args.as_slice()) {
+                    ("sqrt", [arg]) => return format!("\\sqrt{{{}}}", render_latex(arg)),
+                    ("abs", [arg]) => return format!("\\left|{}\\right|", render_latex(arg)),
+                    ("log", [base, arg]) => return format!("\\log_{{{}}}\\left({}\\right)", render_latex(base), render_latex(arg)),
+                    _ => {}
+                }
                 if is_elementary_function(name) {
                     return format!("{}\\left({}\\right)", render_elementary_function(name), args_tex);
                 }
                 return format!("{}\\left({}\\right)", name, args_tex);
             }
+            // Ableitungen von Funktionen einer Variablen mit Strichen, sonst als partielle Ableitungen
+            if let Expr::Derivative(f, indices) = &**func {
+                return format!("{}\\left({}\\right)", render_derivative(f, indices, args.len() == 1), args_tex);
+            }
             format!("\\left[{}\\right]\\left({}\\right)", f, args_tex)
         }
         Expr::UnaryOp(op, e) => {
@@ -41,10 +53,54 @@ pub fn render_latex(expr: &Expr) -> String {
                 UnaryOpKind::Neg => format!("-{}", inner),
             }
         }
+        Expr::Derivative(func, indices) => render_derivative(func, indices, indices.iter().all(|&i| i == 0)),
+        Expr::Matrix(rows) => {
+            let rows = rows
+                .iter()
+                .map(|row| row.iter().map(render_latex).collect::<Vec<_>>().join(" & "))
+                .collect::<Vec<_>>()
+                .join(" \\\\ ");
+            format!("\\begin{{pmatrix}}{}\\end{{pmatrix}}", rows)
+        }
+        Expr::Equation(left, right) => format!("{}={}", render_latex(left), render_latex(right)),
         _ => unimplemented!(),
     }
 }
 
+/// Rendert einen Ableitungsoperator, z.B. \frac{d^{2}}{dx^{2}} oder \frac{\partial^{2}}{\partial x \partial y}.
+/// Gewöhnliche Ableitungen werden nur bei einer einzigen Variablen verwendet.
+pub fn render_derivative_operator(orders: &[(String, usize)]) -> String {
+    let orders: Vec<&(String, usize)> = orders.iter().filter(|(_, order)| *order > 0).collect();
+    let total: usize = orders.iter().map(|(_, order)| order).sum();
+    let power = |symbol: &str, order: usize| if order == 1 { symbol.to_string() } else { format!("{}^{{{}}}", symbol, order) };
+    if let [(var, order)] = orders.as_slice() {
+        return format!("\\frac{{{}}}{{d{}}}", power("d", *order), power(var, *order));
+    }
+    let denominator = orders
+        .iter()
+        .map(|(var, order)| format!("\\partial {}", power(var, *order)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("\\frac{{{}}}{{{}}}", power("\\partial", total), denominator)
+}
+
+/// Rendert eine unausgewertete Ableitung als f', f'', f^{(n)} oder \partial_{1} f.
+fn render_derivative(func: &Expr, indices: &[usize], primes: bool) -> String {
+    let f = match func {
+        Expr::Var(_) => render_latex(func),
+        _ => format!("\\left[{}\\right]", render_latex(func)),
+    };
+    if primes {
+        match indices.len() {
+            n @ 1..=3 => format!("{}{}", f, "'".repeat(n)),
+            n => format!("{}^{{({})}}", f, n),
+        }
+    } else {
+        let partials: String = indices.iter().map(|i| format!("\\partial_{{{}}}", i + 1)).collect();
+        format!("{} {}", partials, f)
+    }
+}
+
 /// Erzeugt eine Liste von Additions-/Subtraktionstermen aus einem verschachtelten binären Add-/Sub-Ausdruck.
 /// Jeder Term wird als Tupel (negiert, &Expr) zurückgegeben – dabei signalisiert das bool,
 /// ob der Term negativ sein soll.
@@ -76,20 +132,19 @@ fn render_add(expr: &Expr) -> String {
     let terms = flatten_add_sub(expr);
     let mut result = String::new();
     for (i, (neg, term)) in terms.iter().enumerate() {
-        let term_str = render_latex(term);
-        if i == 0 {
-            if *neg {
-                result.push_str("-");
-            }
-            result.push_str(&term_str);
-        } else {
-            if *neg {
-                result.push_str("-");
-            } else {
-                result.push_str("+");
-            }
-            result.push_str(&term_str);
+        let mut neg = *neg;
+        let mut term_str = render_latex(term);
+        // Ein führendes Minus im Term wird mit dem Rechenzeichen verrechnet, statt "+-" auszugeben
+        if i > 0 && term_str.starts_with('-') {
+            neg = !neg;
+            term_str.remove(0);
+        }
+        if neg {
+            result.push('-');
+        } else if i > 0 {
+            result.push('+');
         }
+        result.push_str(&term_str);
     }
     result
 }
@@ -125,20 +180,34 @@ fn render_mul(expr: &Expr) -> String {
     let mut denominators = Vec::new();
     for (inv, factor) in factors {
         if inv {
-            denominators.push(render_latex(factor));
+            denominators.push(factor);
         } else {
-            numerators.push(render_latex(factor));
+            numerators.push(factor);
         }
     }
-    let num_str = if numerators.is_empty() { "1".to_string() } else { numerators.join(" \\cdot ") };
+    let num_str = if numerators.is_empty() { "1".to_string() } else { render_factors(&numerators) };
     if denominators.is_empty() {
         num_str
     } else {
-        let den_str = denominators.join(" \\cdot ");
+        let den_str = render_factors(&denominators);
         format!("\\frac{{{}}}{{{}}}", num_str, den_str)
     }
 }
 
+/// Verbindet Faktoren mit \cdot. Summen werden geklammert, sobald sie nicht alleine stehen, z.B. 2 \cdot (x-1).
+fn render_factors(factors: &[&Expr]) -> String {
+    factors
+        .iter()
+        .map(|factor| match factor {
+            Expr::BinaryOp(BinaryOpKind::Add | BinaryOpKind::Sub, _, _) if factors.len() > 1 => {
+                format!("({})", render_latex(factor))
+            }
+            _ => render_latex(factor),
+        })
+        .collect::<Vec<_>>()
+        .join(" \\cdot ")
+}
+
 /// Rendert einen Potenzausdruck. Sonderfälle:
 /// - x⁻¹ wird als Bruch dargestellt.
 /// - x^(1/2) wird als Quadratwurzel dargestellt.
@@ -157,7 +226,7 @@ fn render_pow(base: &Expr, exp: &Expr) -> String {
     // Sonderfall: Trigonometrische Funktionen, z. B. sin(x)^2 → \sin^{2}(x)
     if let Expr::Call(func, args) = base {
         if let Expr::Var(ref name) = **func {
-            if name == "sin" || name == "cos" {
+            if matches!(name.as_str(), "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "sinh" | "cosh" | "tanh") {
                 let arg_tex = if let Some(arg) = args.get(0) {
                     render_latex(arg)
                 } else {
@@ -182,8 +251,49 @@ fn render_elementary_function(name: &str) -> String {
         "exp" => "\\exp".to_string(),
         "sin" => "\\sin".to_string(),
         "cos" => "\\cos".to_string(),
+        "tan" => "\\tan".to_string(),
         "ln"  => "\\ln".to_string(),
+        "cot" => "\\cot".to_string(),
+        "sec" => "\\sec".to_string(),
+        "csc" => "\\csc".to_string(),
+        "asin" => "\\arcsin".to_string(),
+        "acos" => "\\arccos".to_string(),
+        "atan" => "\\arctan".to_string(),
+        "sinh" => "\\sinh".to_string(),
+        "cosh" => "\\cosh".to_string(),
+        "tanh" => "\\tanh".to_string(),
+        "asinh" => "\\operatorname{arsinh}".to_string(),
+        "acosh" => "\\operatorname{arcosh}".to_string(),
+        "atanh" => "\\operatorname{artanh}".to_string(),
+        "sqrt" => "\\sqrt".to_string(),
+        "abs" => "\\operatorname{abs}".to_string(),
+        "sign" => "\\operatorname{sgn}".to_string(),
+        "erf" => "\\operatorname{erf}".to_string(),
+        "gamma" => "\\Gamma".to_string(),
+        "digamma" => "\\psi".to_string(),
         "id"  => "\\mathrm{id}".to_string(),
         _ => panic!("Nicht implementiert"),
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_render_elementary_functions() {
+        assert_eq!(render_latex(&parse("sqrt(x)")), "\\sqrt{x}");
+        assert_eq!(render_latex(&parse("abs(x)")), "\\left|x\\right|");
+        assert_eq!(render_latex(&parse("log(2, x)")), "\\log_{2}\\left(x\\right)");
+        assert_eq!(render_latex(&parse("asinh(x)")), "\\operatorname{arsinh}\\left(x\\right)");
+        assert_eq!(render_latex(&parse("gamma(x)")), "\\Gamma\\left(x\\right)");
+        assert_eq!(render_latex(&parse("sec(x)^2")), "\\sec^{2}\\left(x\\right)");
+    }
+
+    #[test]
+    fn test_render_derivative_operator() {
+        assert_eq!(render_derivative_operator(&[("x".to_string(), 3)]), "\\frac{d^{3}}{dx^{3}}");
+        let mixed = [("x".to_string(), 1), ("y".to_string(), 1)];
+        assert_eq!(render_derivative_operator(&mixed), "\\frac{\\partial^{2}}{\\partial x \\partial y}");
+    }
+}
@@ -0,0 +1,310 @@
+use crate::parser::{BinaryOpKind, Expr};
+use crate::polynomial::{snap, Complex, Polynomial};
+use crate::rational::{ExactPolynomial, Field, Gaussian, Rational};
+
+/// Integriert `numerator / denominator` vollständig, ohne den Nenner über den reellen Zahlen zu faktorisieren.
+/// Die Koeffizienten werden als Brüche gelesen und alle Schritte exakt gerechnet, damit auch eng
+/// beieinanderliegende Nullstellen wie bei `1/((x-1)(x-1.0000001))` getrennt bleiben:
+///
+/// - Der ganzrationale Anteil wird per Polynomdivision abgespalten.
+/// - Die Hermite-Reduktion bestimmt den rationalen Anteil der Stammfunktion und lässt einen Rest
+///   mit quadratfreiem Nenner übrig.
+/// - Der Rest ergibt nach Rothstein-Trager eine Summe `Σ c ln(v_c)` über die Nullstellen `c` der Resultante
+///   `res_x(D, A - z D')` mit `v_c = ggT(D, A - c D')`. Konjugiert komplexe Paare werden nach
+///   Lazard-Rioboo-Trager in reelle Logarithmen und Arkustangens-Terme mit Polynomargumenten umgewandelt.
+///
+/// Gibt einen Fehler zurück, wenn ein Koeffizient irrational ist oder die Brüche zu groß werden.
+pub fn integrate_rational_function(numerator: &Polynomial, denominator: &Polynomial, var: &str) -> Result<Expr, String> {
+    let exact = |polynomial: &Polynomial| {
+        ExactPolynomial::from_polynomial(polynomial).ok_or_else(|| "Error: Rational functions are only integrated with rational coefficients".to_string())
+    };
+    let terms = integrate_exactly(&exact(numerator)?, &exact(denominator)?, var)
+        .ok_or_else(|| "Error: The coefficients are too large for exact integration".to_string())?;
+    Ok(terms
+        .into_iter()
+        .filter(|term| *term != Expr::Number(0.0))
+        .reduce(|acc, term| Expr::BinaryOp(BinaryOpKind::Add, Box::new(acc), Box::new(term)))
+        .unwrap_or(Expr::Number(0.0)))
+}
+
+fn integrate_exactly(numerator: &ExactPolynomial<Rational>, denominator: &ExactPolynomial<Rational>, var: &str) -> Option<Vec<Expr>> {
+    let (quotient, remainder) = numerator.div_rem(denominator)?;
+    let mut terms = vec![quotient.integral()?.to_polynomial().to_expr(var.to_string())];
+    if !remainder.is_zero() {
+        let reduction = hermite_reduce(&remainder, denominator)?;
+        for (b, v, j) in reduction.rational {
+            let power = match j {
+                1 => v.to_polynomial().to_expr(var.to_string()),
+                _ => Expr::BinaryOp(BinaryOpKind::Pow, Box::new(v.to_polynomial().to_expr(var.to_string())), Box::new(Expr::Number(j as f64))),
+            };
+            terms.push(Expr::BinaryOp(BinaryOpKind::Div, Box::new(b.to_polynomial().to_expr(var.to_string())), Box::new(power)));
+        }
+        terms.extend(logarithmic_part(&reduction.numerator, &reduction.denominator, var)?);
+    }
+    Some(terms)
+}
+
+// Ergebnis der Hermite-Reduktion: ∫ A/D = Σ B/V^j + ∫ numerator/denominator
+struct HermiteReduction {
+    rational: Vec<(ExactPolynomial<Rational>, ExactPolynomial<Rational>, usize)>,
+    numerator: ExactPolynomial<Rational>,
+    denominator: ExactPolynomial<Rational>,
+}
+
+// Hermite-Reduktion (Bronstein, Symbolic Integration I, Abschnitt 2.2). Für jeden Faktor V der
+// Vielfachheit i in D = U V^i wird B U V' + C V = -A/j gelöst; dann ist
+// A/(U V^(j+1)) = (B/V^j)' + (-j C - U B') / (U V^j), und der Exponent sinkt, bis V einfach vorkommt.
+// Der verbleibende Nenner ist normiert und quadratfrei.
+fn hermite_reduce(numerator: &ExactPolynomial<Rational>, denominator: &ExactPolynomial<Rational>) -> Option<HermiteReduction> {
+    let mut a = numerator.scale(Rational::one().div(denominator.leading_coefficient())?)?;
+    let mut d = denominator.monic()?;
+    let mut rational = vec![];
+    for (index, v) in denominator.squarefree_factorization()?.iter().enumerate().skip(1) {
+        if v.degree() == 0 {
+            continue;
+        }
+        let i = index + 1;
+        let u = d.exact_div(&v.pow(i)?)?;
+        let uv = u.mul(&v.derivative()?)?;
+        for j in (1..i).rev() {
+            let minus_j = Rational::integer(-(j as i128));
+            let (b, c) = solve_bezout(&uv, v, &a.scale(Rational::one().div(minus_j)?)?)?;
+            a = c.scale(minus_j)?.sub(&u.mul(&b.derivative()?)?)?;
+            rational.push((b, v.clone(), j));
+        }
+        d = u.mul(v)?;
+    }
+    Some(HermiteReduction { rational, numerator: a, denominator: d })
+}
+
+// Löst s a + t b = c mit deg s < deg b; `a` und `b` müssen teilerfremd sein oder ihr ggT muss `c` teilen
+fn solve_bezout(
+    a: &ExactPolynomial<Rational>,
+    b: &ExactPolynomial<Rational>,
+    c: &ExactPolynomial<Rational>,
+) -> Option<(ExactPolynomial<Rational>, ExactPolynomial<Rational>)> {
+    let (g, s, t) = a.extended_gcd(b)?;
+    let q = c.exact_div(&g)?;
+    let (quotient, s) = s.mul(&q)?.div_rem(b)?;
+    Some((s, t.mul(&q)?.add(&quotient.mul(a)?)?))
+}
+
+// Logarithmischer Anteil von ∫ A/D bei normiertem, quadratfreiem D und deg A < deg D. Die Residuen
+// c = A(α)/D'(α) an den Nullstellen α von D sind genau die Nullstellen der Resultante
+// R(z) = res_x(D, A - z D'), und v_c = ggT(D, A - c D') ist das Produkt der (x - α) mit diesem Residuum.
+// Residuen, die (komplex) rational sind, werden exakt bestimmt; die übrigen, z.B. ±√2/4 bei 1/(x²-2),
+// nur numerisch.
+fn logarithmic_part(numerator: &ExactPolynomial<Rational>, denominator: &ExactPolynomial<Rational>, var: &str) -> Option<Vec<Expr>> {
+    let derivative = denominator.derivative()?;
+    // R hat den Grad deg D und wird aus deg D + 1 Werten interpoliert
+    let points = (0..=denominator.degree() as i128)
+        .map(|k| {
+            let z = Rational::integer(k);
+            Some((z, denominator.resultant(&numerator.sub(&derivative.scale(z)?)?)?))
+        })
+        .collect::<Option<Vec<(Rational, Rational)>>>()?;
+    let resultant = ExactPolynomial::interpolate(&points)?;
+
+    // Die Faktoren der quadratfreien Zerlegung haben einfache Nullstellen, die numerisch gut bestimmt sind
+    let (mut exact, mut approximate) = (vec![], vec![]);
+    for factor in resultant.squarefree_factorization()? {
+        for root in factor.to_polynomial().roots() {
+            match exact_root(&factor, root) {
+                Some(residue) => exact.push(residue),
+                None => approximate.push(root),
+            }
+        }
+    }
+
+    let mut terms = vec![];
+    for residue in &exact {
+        if residue.im.is_negative() || residue.is_zero() {
+            continue;
+        }
+        // v_c = p + i q mit reellen Polynomen p und q
+        let (a, d) = (numerator.to_gaussian(), denominator.to_gaussian());
+        let v = d.gcd(&a.sub(&d.derivative()?.scale(*residue)?)?)?;
+        let (p, q) = v.parts();
+        let (p, q) = (p.to_polynomial(), q.to_polynomial());
+        let (re, im) = (residue.re.to_f64(), residue.im.to_f64());
+        if residue.im == Rational::zero() {
+            let abs = Expr::Call(Box::new(Expr::Var("abs".to_string())), vec![p.to_expr(var.to_string())]);
+            terms.push(scaled(re, call("ln", abs)));
+        } else {
+            // Zusammen mit dem konjugierten Paar: c ln(v) + c̄ ln(v̄) = re ln(p² + q²) + im i ln((p + iq)/(p - iq))
+            if re != 0.0 {
+                terms.push(scaled(re, call("ln", p.mul(&p).add(&q.mul(&q)).to_expr(var.to_string()))));
+            }
+            terms.extend(log_to_atan(&p, &q, im, var));
+        }
+    }
+    let exact: Vec<Complex> = exact.iter().map(|c| Complex::new(c.re.to_f64(), c.im.to_f64())).collect();
+    terms.extend(approximate_logarithms(&numerator.to_polynomial(), &denominator.to_polynomial(), &exact, &approximate, var));
+    Some(terms)
+}
+
+// Prüft, ob die numerische Nullstelle `root` von `factor` eine (komplex) rationale Zahl ist
+fn exact_root(factor: &ExactPolynomial<Rational>, root: Complex) -> Option<Gaussian> {
+    let residue = Gaussian::new(Rational::approximate(root.re)?, Rational::approximate(root.im)?);
+    factor.to_gaussian().evaluate(residue)?.is_zero().then_some(residue)
+}
+
+// Logarithmen zu den nur numerisch bekannten Residuen `approximate`: Jede Nullstelle α von D gehört zu dem
+// Residuum, das A(α)/D'(α) am nächsten liegt, und v_c ist das Produkt der zugehörigen (x - α).
+fn approximate_logarithms(numerator: &Polynomial, denominator: &Polynomial, exact: &[Complex], approximate: &[Complex], var: &str) -> Vec<Expr> {
+    if approximate.is_empty() {
+        return vec![];
+    }
+    let derivative = denominator.derivative();
+    let residues: Vec<Complex> = exact.iter().chain(approximate).copied().collect();
+    let mut groups = vec![vec![]; approximate.len()];
+    for root in denominator.roots() {
+        let residue = numerator.evaluate_complex(root).div(derivative.evaluate_complex(root));
+        if let Some(k) = nearest(&residues, residue).checked_sub(exact.len()) {
+            groups[k].push(root);
+        }
+    }
+
+    let mut terms = vec![];
+    for (k, (residue, roots)) in approximate.iter().zip(groups).enumerate() {
+        let v = roots.iter().fold(vec![Complex::new(1.0, 0.0)], |coeffs, root| {
+            let mut product = vec![Complex::new(0.0, 0.0); coeffs.len() + 1];
+            for (k, c) in coeffs.iter().enumerate() {
+                product[k + 1] = product[k + 1].add(*c);
+                product[k] = product[k].sub(c.mul(*root));
+            }
+            product
+        });
+        let p = Polynomial::new(v.iter().map(|z| snap(z.re)).collect());
+        let q = Polynomial::new(v.iter().map(|z| snap(z.im)).collect());
+        // Ein reelles Residuum ist sein eigenes konjugiertes
+        if nearest(approximate, Complex::new(residue.re, -residue.im)) == k {
+            let abs = Expr::Call(Box::new(Expr::Var("abs".to_string())), vec![p.to_expr(var.to_string())]);
+            terms.push(scaled(residue.re, call("ln", abs)));
+        } else if residue.im > 0.0 {
+            if snap(residue.re) != 0.0 {
+                terms.push(scaled(residue.re, call("ln", p.mul(&p).add(&q.mul(&q)).to_expr(var.to_string()))));
+            }
+            terms.extend(log_to_atan(&p, &q, residue.im, var));
+        }
+    }
+    terms
+}
+
+fn nearest(candidates: &[Complex], z: Complex) -> usize {
+    (0..candidates.len()).min_by(|&i, &j| candidates[i].sub(z).abs().total_cmp(&candidates[j].sub(z).abs())).unwrap_or(0)
+}
+
+// Rioboo: i ln((a + ib)/(a - ib)) hat dieselbe Ableitung wie eine Summe 2 Σ atan(h_k) mit Polynomen h_k.
+// Anders als 2 atan(a/b) hat diese Darstellung keine Sprungstellen an den Nullstellen von b.
+fn log_to_atan(a: &Polynomial, b: &Polynomial, scale: f64, var: &str) -> Vec<Expr> {
+    let Ok((quotient, remainder)) = a.div_rem(b) else {
+        return vec![];
+    };
+    if remainder.is_zero() {
+        return vec![scaled(2.0 * scale, call("atan", snapped(&quotient).to_expr(var.to_string())))];
+    }
+    if a.degree() < b.degree() {
+        return log_to_atan(&b.scale(-1.0), a, scale, var);
+    }
+    // b d - a c = g
+    let (g, d, c) = b.extended_gcd(&a.scale(-1.0));
+    let argument = a.mul(&d).add(&b.mul(&c)).exact_div(&g);
+    let mut terms = vec![scaled(2.0 * scale, call("atan", snapped(&argument).to_expr(var.to_string())))];
+    terms.extend(log_to_atan(&d, &c, scale, var));
+    terms
+}
+
+fn snapped(polynomial: &Polynomial) -> Polynomial {
+    Polynomial::new(polynomial.coeffs.iter().map(|c| snap(*c)).collect())
+}
+
+fn scaled(factor: f64, expr: Expr) -> Expr {
+    Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(snap(factor))), Box::new(expr))
+}
+
+fn call(name: &str, argument: Expr) -> Expr {
+    Expr::Call(Box::new(Expr::Var(name.to_string())), vec![argument])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differentiate::differentiate;
+    use crate::evaluate::{evaluate, Environment};
+    use crate::render::render_latex;
+    use crate::simplify::simplify;
+
+    fn polynomial(coeffs: &[f64]) -> Polynomial {
+        Polynomial::new(coeffs.to_vec())
+    }
+
+    // Prüft die Stammfunktion durch Ableiten an einigen Stellen
+    fn check(numerator: Polynomial, denominator: Polynomial, points: &[f64]) -> Expr {
+        let integral = integrate_rational_function(&numerator, &denominator, "x").unwrap();
+        let derivative = differentiate(integral.clone(), "x".to_string());
+        let mut env = Environment::new();
+        for &x in points {
+            env.set_variable("x".to_string(), x);
+            let expected = numerator.evaluate_complex(Complex::new(x, 0.0)).re / denominator.evaluate_complex(Complex::new(x, 0.0)).re;
+            let found = evaluate(&derivative, &env).unwrap();
+            assert!((expected - found).abs() < 1e-8 * (1.0 + expected.abs()), "{}: {} != {} at {}", render_latex(&integral), found, expected, x);
+        }
+        simplify(integral, false)
+    }
+
+    #[test]
+    fn test_hermite_reduction() {
+        // ∫ 1/(x^2+1)^2 dx = x / (2 (x^2+1)) + atan(x)/2
+        let exact = |polynomial: Polynomial| ExactPolynomial::from_polynomial(&polynomial).unwrap();
+        let reduction = hermite_reduce(&exact(polynomial(&[1.0])), &exact(polynomial(&[1.0, 0.0, 1.0]).pow(2))).unwrap();
+        let [(b, v, 1)] = &reduction.rational[..] else {
+            panic!("expected one rational term");
+        };
+        assert_eq!((b.to_polynomial(), v.to_polynomial()), (polynomial(&[0.0, 0.5]), polynomial(&[1.0, 0.0, 1.0])));
+        assert_eq!(reduction.numerator.to_polynomial(), polynomial(&[0.5]));
+        assert_eq!(reduction.denominator.to_polynomial(), polynomial(&[1.0, 0.0, 1.0]));
+        check(polynomial(&[1.0]), polynomial(&[1.0, 0.0, 1.0]).pow(2), &[-1.0, 0.5, 3.0]);
+    }
+
+    #[test]
+    fn test_equal_residues_share_a_logarithm() {
+        // Beide Nullstellen ±√2 haben das Residuum 1
+        let integral = check(polynomial(&[0.0, 2.0]), polynomial(&[-2.0, 0.0, 1.0]), &[2.0, 0.5]);
+        assert_eq!(render_latex(&integral), "\\ln\\left(\\left|x^{2}-2\\right|\\right)");
+        let integral = check(polynomial(&[1.0]), polynomial(&[1.0, 0.0, 1.0]), &[0.5]);
+        assert_eq!(render_latex(&integral), "\\arctan\\left(x\\right)");
+    }
+
+    #[test]
+    fn test_logarithms_and_arctangents() {
+        check(polynomial(&[1.0]), polynomial(&[1.0, 0.0, 0.0, 1.0]), &[0.0, 1.5, -0.5]);
+        check(polynomial(&[1.0]), polynomial(&[1.0, 0.0, 0.0, 0.0, 1.0]), &[-2.0, 0.0, 0.7]);
+        check(polynomial(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0]), polynomial(&[1.0, 0.0, 1.0]).pow(3), &[-1.0, 2.0]);
+        // (x^2 + 3) / ((x - 1)^3 (x^2 + x + 1)^2)
+        let denominator = polynomial(&[-1.0, 1.0]).pow(3).mul(&polynomial(&[1.0, 1.0, 1.0]).pow(2));
+        check(polynomial(&[3.0, 0.0, 1.0]), denominator, &[-1.0, 0.3, 2.5]);
+        // Beispiel von Rioboo: Die Arkustangens-Terme haben Polynomargumente und sind stetig
+        let numerator = polynomial(&[6.0, 0.0, -3.0, 0.0, 1.0]);
+        let denominator = polynomial(&[4.0, 0.0, 5.0, 0.0, -5.0, 0.0, 1.0]);
+        let integral = check(numerator, denominator, &[-3.0, -1.0, 0.2, 1.3, 4.0]);
+        assert_eq!(
+            render_latex(&integral),
+            "\\arctan\\left(0.5 \\cdot x^{5}-1.5 \\cdot x^{3}+0.5 \\cdot x\\right)+\\arctan\\left(x^{3}\\right)+\\arctan\\left(x\\right)"
+        );
+    }
+
+    #[test]
+    fn test_close_roots_stay_apart() {
+        // Die Nullstellen 1 und 1.0000001 haben die Residuen -10^7 und 10^7
+        let denominator = polynomial(&[-1.0, 1.0]).mul(&polynomial(&[-1.0000001, 1.0]));
+        let integral = check(polynomial(&[1.0]), denominator, &[0.5, 3.0]);
+        assert_eq!(
+            render_latex(&integral),
+            "10000000 \\cdot \\ln\\left(\\left|x-1.0000001\\right|\\right)-10000000 \\cdot \\ln\\left(\\left|x-1\\right|\\right)"
+        );
+        // π wird als Bruch gelesen; die Residuen ±i/(2√π) sind irrational und werden numerisch bestimmt
+        let integral = check(polynomial(&[1.0]), polynomial(&[std::f64::consts::PI, 0.0, 1.0]), &[0.5, 3.0]);
+        assert!(render_latex(&integral).starts_with("0.5641895835477"));
+    }
+}
@@ -1,13 +1,27 @@
+use crate::assumptions::{is_even, sign};
+use crate::constants::{E, PI};
 use crate::differentiate::is_elementary_function;
-use crate::parser::{BinaryOpKind, Expr};
+use crate::evaluate::{elementary, log};
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+use crate::polynomial::rational_approximation;
 
 pub fn simplify(expr: Expr, s: bool) -> Expr {
     // s = simplify_elementary_function
     match expr {
         // Wenn die Expression eine Zahl ist, wird sie unverändert zurückgegeben
         Expr::Number(_) => expr,
+        // Bei numerischer Auswertung werden die Konstanten pi und e eingesetzt
+        Expr::Var(name) if s && name == "pi" => Expr::Number(PI),
+        Expr::Var(name) if s && name == "e" => Expr::Number(E),
         // Wenn die Expression eine Variable ist, wird sie unverändert zurückgegeben
         Expr::Var(_) => expr,
+        // Unausgewertete Ableitungen bleiben stehen
+        Expr::Derivative(_, _) => expr,
+        // Matrizen werden eintragsweise vereinfacht
+        Expr::Matrix(rows) => Expr::Matrix(
+            rows.into_iter().map(|row| row.into_iter().map(|entry| simplify(entry, s)).collect()).collect(),
+        ),
+        Expr::Equation(left, right) => Expr::Equation(Box::new(simplify(*left, s)), Box::new(simplify(*right, s))),
         // Wenn die Expression eine binäre Operation ist, wird die Vereinfachung rekursiv auf die Operanden angewendet
         Expr::BinaryOp(op, left, right) => {
             let left = simplify(*left, s);
@@ -20,7 +34,21 @@ pub fn simplify(expr: Expr, s: bool) -> Expr {
                 (BinaryOpKind::Div, Expr::Number(a), Expr::Number(b)) => Expr::Number(a / b),
                 (BinaryOpKind::Pow, _, Expr::Number(0.0)) => Expr::Number(1.0),
                 (BinaryOpKind::Pow, left, Expr::Number(1.0)) => left,
-                (BinaryOpKind::Pow, Expr::Number(a), Expr::Number(b)) => Expr::Number(a.powf(b)),
+                // Irrationale Potenzen wie 2^0.5 bleiben exakt stehen, außer bei numerischer Auswertung
+                (BinaryOpKind::Pow, Expr::Number(a), Expr::Number(b)) if s || b.fract() == 0.0 || a.powf(b).fract() == 0.0 => Expr::Number(a.powf(b)),
+                // (x^a)^b = x^(ab) gilt nur für ganzzahlige b oder x >= 0, sonst z.B. sqrt(x^2) = |x|
+                (BinaryOpKind::Pow, Expr::BinaryOp(BinaryOpKind::Pow, base, a), Expr::Number(b)) if b.fract() == 0.0 || sign(&base).is_nonnegative() => {
+                    let exponent = simplify(Expr::BinaryOp(BinaryOpKind::Mul, a, Box::new(Expr::Number(b))), s);
+                    simplify(Expr::BinaryOp(BinaryOpKind::Pow, base, Box::new(exponent)), s)
+                }
+                // sqrt(x^2) = -x für x <= 0, sonst |x|
+                (BinaryOpKind::Pow, Expr::BinaryOp(BinaryOpKind::Pow, base, a), Expr::Number(b)) if is_even(&a) && matches!(*a, Expr::Number(a) if a * b == 1.0) => {
+                    if sign(&base).is_nonpositive() {
+                        simplify(Expr::UnaryOp(UnaryOpKind::Neg, base), s)
+                    } else {
+                        Expr::Call(Box::new(Expr::Var("abs".to_string())), vec![*base])
+                    }
+                }
                 (BinaryOpKind::Mul, Expr::Number(0.0), _) => Expr::Number(0.0),
                 (BinaryOpKind::Mul, _, Expr::Number(0.0)) => Expr::Number(0.0),
                 (BinaryOpKind::Mul, Expr::Number(1.0), right) => right,
@@ -28,6 +56,20 @@ pub fn simplify(expr: Expr, s: bool) -> Expr {
                 (BinaryOpKind::Div, left, Expr::Number(1.0)) => left,
                 (BinaryOpKind::Add, left, Expr::Number(0.0)) => left,
                 (BinaryOpKind::Add, Expr::Number(0.0), right) => right,
+                // exp(a) * exp(b) = exp(a+b) und exp(a) / exp(b) = exp(a-b)
+                (BinaryOpKind::Mul | BinaryOpKind::Div, Expr::Call(f, a), Expr::Call(g, b)) if is_exp(&f, &a) && is_exp(&g, &b) => {
+                    let combined = if op == BinaryOpKind::Mul { BinaryOpKind::Add } else { BinaryOpKind::Sub };
+                    let argument = simplify(Expr::BinaryOp(combined, Box::new(a[0].clone()), Box::new(b[0].clone())), s);
+                    Expr::Call(f, vec![argument])
+                }
+                // x^a * x^b = x^(a+b) und x^a / x^b = x^(a-b)
+                (BinaryOpKind::Mul | BinaryOpKind::Div, left, right) if !matches!(left, Expr::Number(_)) && power_parts(&left).0 == power_parts(&right).0 => {
+                    let (base, a) = power_parts(&left);
+                    let (_, b) = power_parts(&right);
+                    let combined = if op == BinaryOpKind::Mul { BinaryOpKind::Add } else { BinaryOpKind::Sub };
+                    let exponent = simplify(Expr::BinaryOp(combined, Box::new(a), Box::new(b)), s);
+                    simplify(Expr::BinaryOp(BinaryOpKind::Pow, Box::new(base), Box::new(exponent)), s)
+                }
                 _ => Expr::BinaryOp(op, Box::new(left), Box::new(right)),
             }
         }
@@ -69,21 +111,26 @@ fn simplify_call(func: Expr, args: &Vec<Expr>, s: bool) -> Expr {
     // s = simplify_elementary_function
     let args: Vec<Expr> = args.iter().map(|arg| simplify(arg.clone(), s)).collect();
     match func.clone() {
+        // Konstanten sind in punktfreien Ausdrücken konstante Funktionen
+        Expr::Var(name) if name == "pi" || name == "e" => simplify(func, s),
+        Expr::Var(name) if name == "log" && args.len() == 2 => simplify_log(&args[0], &args[1], s),
         Expr::Var(name) => {
-            if name == "id" {
-                assert!(args.len() == 1);
+            if name == "id" && args.len() == 1 {
                 return args[0].clone()
-            } else if is_elementary_function(&name){
-                if s {
-                    evaluate_elementary_function(&name, args.clone());
+            } else if is_elementary_function(&name) && args.len() == 1 {
+                if let Some(value) = simplify_elementary_call(&name, &args[0], s) {
+                    return value;
                 }
                 return Expr::Call(Box::new(func.clone()), args.clone());
             }
-            Expr::Var(name.clone())
+            // Unbekannte Funktionen bleiben als Aufruf mit ihren Argumenten erhalten
+            Expr::Call(Box::new(func.clone()), args.clone())
         },
         Expr::Number(_) => func.clone(),
-        Expr::Call(func, args) => {
-            simplify_call(*func, &args, s)
+        // Verkettung punktfreier Funktionen: f(g)(x) = f(g(x))
+        Expr::Call(outer, inner) => {
+            let inner: Vec<Expr> = inner.into_iter().map(|g| simplify_call(g, &args, s)).collect();
+            simplify(Expr::Call(outer, inner), s)
         },
         Expr::UnaryOp(op, expr) => {
             Expr::UnaryOp(op, Box::new(simplify_call(*expr, &args, s)))
@@ -95,31 +142,467 @@ fn simplify_call(func: Expr, args: &Vec<Expr>, s: bool) -> Expr {
     }
 }
 
-fn evaluate_elementary_function(name: &str, args: Vec<Expr>) -> Expr {
-    // Elementare Funktionen haben nur ein Argument
-    assert!(args.len() == 1);
-    let arg = if let Expr::Number(x) = args[0].clone() {
-        x
-    } else {
-        panic!("Error: Expected number as argument for elementary function");
-    };
-    // Berechnung der elementaren Funktion
+// Paare (f, g) mit f(g(u)) = u für alle reellen u im Definitionsbereich von g
+const INVERSE_PAIRS: &[(&str, &str)] = &[
+    ("exp", "ln"), ("ln", "exp"), ("sin", "asin"), ("cos", "acos"), ("tan", "atan"), ("sinh", "asinh"),
+    ("asinh", "sinh"), ("cosh", "acosh"), ("tanh", "atanh"), ("atanh", "tanh"),
+];
+
+// Vereinfacht den Aufruf einer elementaren Funktion mit einem Argument, None falls nichts zu tun ist
+fn simplify_elementary_call(name: &str, arg: &Expr, s: bool) -> Option<Expr> {
+    let call = |name: &str, arg: Expr| Expr::Call(Box::new(Expr::Var(name.to_string())), vec![arg]);
+    // Umkehrfunktionen heben sich auf, z.B. exp(ln(x)) = x, sinh(asinh(x)) = x
+    if let Expr::Call(inner, inner_args) = arg {
+        if let (Expr::Var(inner_name), [inner_arg]) = (&**inner, inner_args.as_slice()) {
+            if INVERSE_PAIRS.contains(&(name, inner_name.as_str())) {
+                return Some(inner_arg.clone());
+            }
+            // |abs(u)| = |u|
+            if name == "abs" && inner_name == "abs" {
+                return Some(arg.clone());
+            }
+        }
+    }
     match name {
-        "id" => {
-            Expr::Number(arg)
-        },
-        "exp" => {
-            Expr::Number(arg.exp())
-        },
-        "ln" => {
-            Expr::Number(arg.ln())
+        // sqrt(u) = u^(1/2), so werden Wurzeln wie Potenzen zusammengefasst
+        "sqrt" => return Some(simplify(Expr::BinaryOp(BinaryOpKind::Pow, Box::new(arg.clone()), Box::new(Expr::Number(0.5))), s)),
+        // |u| und sign(u) über das Vorzeichen aus den Annahmen, |-u| = |u|
+        "abs" if sign(arg).is_nonnegative() => return Some(arg.clone()),
+        "abs" if sign(arg).is_nonpositive() => return Some(simplify(Expr::UnaryOp(UnaryOpKind::Neg, Box::new(arg.clone())), s)),
+        "abs" => {
+            if let Expr::UnaryOp(UnaryOpKind::Neg, inner) = arg {
+                return Some(call("abs", (**inner).clone()));
+            }
+        }
+        "sign" if sign(arg).is_positive() => return Some(Expr::Number(1.0)),
+        "sign" if sign(arg).is_negative() => return Some(Expr::Number(-1.0)),
+        _ => {}
+    }
+    if let Expr::Number(x) = arg {
+        // Exakte Werte wie sin(0) = 0, exp(0) = 1 und ln(1) = 0 werden immer eingesetzt,
+        // alle anderen nur bei numerischer Auswertung
+        if let Some(value) = exact_elementary_value(name, *x) {
+            return Some(Expr::Number(value));
+        }
+        if s {
+            if let Ok(value) = elementary(name, *x) {
+                return Some(Expr::Number(value));
+            }
+        } else if let Some((p, q)) = exact_pi_multiple(name, *x) {
+            let pi = Expr::Var("pi".to_string());
+            let multiple = match p {
+                1.0 => pi,
+                _ => Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(p.abs())), Box::new(pi)),
+            };
+            let multiple = match q {
+                1.0 => multiple,
+                _ => Expr::BinaryOp(BinaryOpKind::Div, Box::new(multiple), Box::new(Expr::Number(q))),
+            };
+            return Some(if p < 0.0 { Expr::UnaryOp(UnaryOpKind::Neg, Box::new(multiple)) } else { multiple });
+        }
+    }
+    None
+}
+
+// log(b, 1) = 0, log(b, b) = 1, numerisch als ln(x) / ln(b)
+fn simplify_log(base: &Expr, arg: &Expr, s: bool) -> Expr {
+    let unevaluated = || Expr::Call(Box::new(Expr::Var("log".to_string())), vec![base.clone(), arg.clone()]);
+    match (base, arg) {
+        (_, Expr::Number(1.0)) => Expr::Number(0.0),
+        (base, arg) if base == arg => Expr::Number(1.0),
+        (Expr::Number(b), Expr::Number(x)) if s => match log(*b, *x) {
+            Ok(value) => Expr::Number(value),
+            Err(_) => unevaluated(),
         },
-        "sin" => {
-            Expr::Number(arg.sin())
+        _ => unevaluated(),
+    }
+}
+
+// Prüft, ob ein Aufruf exp(u) ist
+fn is_exp(func: &Expr, args: &[Expr]) -> bool {
+    matches!(func, Expr::Var(name) if name == "exp") && args.len() == 1
+}
+
+// Zerlegt einen Ausdruck in Basis und Exponent, x wird als x^1 aufgefasst
+fn power_parts(expr: &Expr) -> (Expr, Expr) {
+    match expr {
+        Expr::BinaryOp(BinaryOpKind::Pow, base, exponent) => ((**base).clone(), (**exponent).clone()),
+        _ => (expr.clone(), Expr::Number(1.0)),
+    }
+}
+
+// Exakte Funktionswerte, die auch ohne numerische Auswertung eingesetzt werden
+fn exact_elementary_value(name: &str, arg: f64) -> Option<f64> {
+    match (name, arg) {
+        ("sin" | "tan" | "atan" | "asin" | "sinh" | "tanh" | "asinh" | "atanh" | "erf", 0.0) => Some(0.0),
+        ("cos" | "exp" | "cosh", 0.0) => Some(1.0),
+        ("ln" | "acos" | "acosh", 1.0) => Some(0.0),
+        ("abs", x) => Some(x.abs()),
+        ("sign", x) => elementary("sign", x).ok(),
+        // Γ(n) = (n-1)! für kleine natürliche Zahlen
+        ("gamma", n) if (1.0..=20.0).contains(&n) && n.fract() == 0.0 => elementary("gamma", n).ok(),
+        _ => None,
+    }
+}
+
+// Exakte Werte der Arkusfunktionen als Bruch p/q von π, z.B. atan(1) = π/4
+fn exact_pi_multiple(name: &str, arg: f64) -> Option<(f64, f64)> {
+    match (name, arg) {
+        ("atan", 1.0) => Some((1.0, 4.0)),
+        ("atan", -1.0) => Some((-1.0, 4.0)),
+        ("asin", 1.0) => Some((1.0, 2.0)),
+        ("asin", -1.0) => Some((-1.0, 2.0)),
+        ("asin", 0.5) => Some((1.0, 6.0)),
+        ("asin", -0.5) => Some((-1.0, 6.0)),
+        ("acos", 0.0) => Some((1.0, 2.0)),
+        ("acos", 0.5) => Some((1.0, 3.0)),
+        ("acos", -0.5) => Some((2.0, 3.0)),
+        ("acos", -1.0) => Some((1.0, 1.0)),
+        _ => None,
+    }
+}
+
+/// Multipliziert Produkte von Summen sowie ganzzahlige Potenzen von Summen aus,
+/// z.B. `(x+1)^2 = x^2 + 2x + 1`. Das Ergebnis ist bereits mit `collect` zusammengefasst.
+pub fn expand(expr: Expr) -> Expr {
+    let expanded = match expr {
+        Expr::BinaryOp(op, left, right) => {
+            let left = expand(*left);
+            let right = expand(*right);
+            match op {
+                BinaryOpKind::Mul => {
+                    // Jeder Summand links wird mit jedem Summanden rechts multipliziert
+                    let mut products = vec![];
+                    for (a, left_term) in terms(&left) {
+                        for (b, right_term) in terms(&right) {
+                            products.push((a * b, multiply(left_term.clone(), right_term)));
+                        }
+                    }
+                    build_sum(products)
+                }
+                BinaryOpKind::Div => {
+                    let quotients = terms(&left)
+                        .into_iter()
+                        .map(|(a, term)| (a, Expr::BinaryOp(BinaryOpKind::Div, Box::new(term), Box::new(right.clone()))))
+                        .collect();
+                    build_sum(quotients)
+                }
+                BinaryOpKind::Pow => match right {
+                    Expr::Number(n) if n.fract() == 0.0 && (2.0..=16.0).contains(&n) && terms(&left).len() > 1 => {
+                        let mut result = left.clone();
+                        for _ in 1..n as usize {
+                            result = expand(Expr::BinaryOp(BinaryOpKind::Mul, Box::new(result), Box::new(left.clone())));
+                        }
+                        result
+                    }
+                    _ => Expr::BinaryOp(op, Box::new(left), Box::new(right)),
+                },
+                _ => Expr::BinaryOp(op, Box::new(left), Box::new(right)),
+            }
+        }
+        Expr::UnaryOp(op, inner) => Expr::UnaryOp(op, Box::new(expand(*inner))),
+        Expr::Call(func, args) => Expr::Call(func, args.into_iter().map(expand).collect()),
+        _ => expr,
+    };
+    collect(expanded)
+}
+
+/// Fasst gleichartige Summanden und Faktoren zusammen,
+/// z.B. `x*y + 2*y*x = 3*x*y`, `x*x = x^2` und `x^3/x = x^2`.
+pub fn collect(expr: Expr) -> Expr {
+    let expr = match expr {
+        Expr::BinaryOp(op, left, right) => Expr::BinaryOp(op, Box::new(collect(*left)), Box::new(collect(*right))),
+        Expr::UnaryOp(op, inner) => Expr::UnaryOp(op, Box::new(collect(*inner))),
+        Expr::Call(func, args) => Expr::Call(func, args.into_iter().map(collect).collect()),
+        _ => expr,
+    };
+    let expr = simplify(expr, false);
+
+    let mut collected: Vec<(f64, Expr, String)> = vec![];
+    for (coefficient, term) in terms(&expr) {
+        let (factor_coefficient, term) = collect_factors(&term);
+        let key = monomial_key(&term);
+        match collected.iter_mut().find(|(_, _, other)| *other == key) {
+            Some(entry) => entry.0 += coefficient * factor_coefficient,
+            None => collected.push((coefficient * factor_coefficient, term, key)),
+        }
+    }
+    build_sum(collected.into_iter().map(|(coefficient, term, _)| (coefficient, term)).collect())
+}
+
+/// Zerlegt eine Summe in ihre Summanden, jeweils als `(Koeffizient, Term)`.
+/// Zahlen werden als `(Zahl, 1)` zurückgegeben.
+pub fn terms(expr: &Expr) -> Vec<(f64, Expr)> {
+    match expr {
+        Expr::BinaryOp(BinaryOpKind::Add, left, right) => {
+            let mut result = terms(left);
+            result.extend(terms(right));
+            result
+        }
+        Expr::BinaryOp(BinaryOpKind::Sub, left, right) => {
+            let mut result = terms(left);
+            result.extend(terms(right).into_iter().map(|(c, term)| (-c, term)));
+            result
+        }
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => terms(inner).into_iter().map(|(c, term)| (-c, term)).collect(),
+        _ => vec![split_coefficient(expr)],
+    }
+}
+
+/// Trennt einen Zahlenfaktor von einem Produkt ab, z.B. `3*x` → `(3, x)`.
+pub fn split_coefficient(expr: &Expr) -> (f64, Expr) {
+    match expr {
+        Expr::Number(c) => (*c, Expr::Number(1.0)),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => {
+            let (c, rest) = split_coefficient(inner);
+            (-c, rest)
+        }
+        Expr::BinaryOp(BinaryOpKind::Mul, left, right) => {
+            let (a, left) = split_coefficient(left);
+            let (b, right) = split_coefficient(right);
+            (a * b, multiply(left, right))
+        }
+        Expr::BinaryOp(BinaryOpKind::Div, left, right) => match **right {
+            Expr::Number(d) if d != 0.0 => {
+                let (a, left) = split_coefficient(left);
+                (a / d, left)
+            }
+            _ => {
+                let (a, left) = split_coefficient(left);
+                let rest = if left == Expr::Number(1.0) {
+                    Expr::BinaryOp(BinaryOpKind::Div, Box::new(Expr::Number(1.0)), right.clone())
+                } else {
+                    Expr::BinaryOp(BinaryOpKind::Div, Box::new(left), right.clone())
+                };
+                (a, rest)
+            }
         },
-        "cos" => {
-            Expr::Number(arg.cos())
+        _ => (1.0, expr.clone()),
+    }
+}
+
+/// Setzt eine Summe aus `(Koeffizient, Term)`-Paaren zusammen. Negative Koeffizienten werden
+/// als Subtraktion dargestellt, Summanden mit Koeffizient 0 entfallen.
+pub fn build_sum(terms: Vec<(f64, Expr)>) -> Expr {
+    let mut result: Option<Expr> = None;
+    for (coefficient, term) in terms {
+        if coefficient == 0.0 {
+            continue;
+        }
+        let magnitude = match (coefficient.abs(), term) {
+            (c, Expr::Number(n)) => Expr::Number(c * n),
+            (1.0, term) => term,
+            // Einfache Brüche werden als p*term/q statt als Dezimalzahl geschrieben
+            (c, term) => match rational_approximation(c, 100) {
+                Some((p, q)) if q > 1 => Expr::BinaryOp(
+                    BinaryOpKind::Div,
+                    Box::new(multiply(Expr::Number(p as f64), term)),
+                    Box::new(Expr::Number(q as f64)),
+                ),
+                _ => Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(c)), Box::new(term)),
+            },
+        };
+        result = Some(match result {
+            None if coefficient < 0.0 => match magnitude {
+                Expr::Number(n) => Expr::Number(-n),
+                magnitude => Expr::UnaryOp(UnaryOpKind::Neg, Box::new(magnitude)),
+            },
+            None => magnitude,
+            Some(acc) if coefficient < 0.0 => Expr::BinaryOp(BinaryOpKind::Sub, Box::new(acc), Box::new(magnitude)),
+            Some(acc) => Expr::BinaryOp(BinaryOpKind::Add, Box::new(acc), Box::new(magnitude)),
+        });
+    }
+    result.unwrap_or(Expr::Number(0.0))
+}
+
+// Multipliziert zwei Terme und lässt dabei Einsen weg
+fn multiply(left: Expr, right: Expr) -> Expr {
+    match (left, right) {
+        (Expr::Number(1.0), right) => right,
+        (left, Expr::Number(1.0)) => left,
+        (left, right) => Expr::BinaryOp(BinaryOpKind::Mul, Box::new(left), Box::new(right)),
+    }
+}
+
+// Fasst gleiche Basen in einem Produkt zusammen und gibt den Zahlenfaktor separat zurück.
+// Symbolische Exponenten werden addiert (x^a * x^b = x^(a+b)), ebenso exp-Argumente (exp(a) exp(b) = exp(a+b)).
+fn collect_factors(expr: &Expr) -> (f64, Expr) {
+    let mut coefficient = 1.0;
+    let mut factors: Vec<(Expr, Expr)> = vec![];
+    let mut exp_argument: Option<Expr> = None;
+    for (base, exponent) in flatten_factors(expr, 1.0) {
+        // Wie in `simplify` bleiben irrationale Potenzen von Zahlen stehen
+        if let Expr::Number(n) = base {
+            if exponent.fract() == 0.0 || n.powf(exponent).fract() == 0.0 {
+                coefficient *= n.powf(exponent);
+                continue;
+            }
+        }
+        if let Expr::Call(func, args) = &base {
+            if is_exp(func, args) {
+                let scaled = Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(exponent)), Box::new(args[0].clone()));
+                exp_argument = Some(match exp_argument {
+                    None => scaled,
+                    Some(sum) => Expr::BinaryOp(BinaryOpKind::Add, Box::new(sum), Box::new(scaled)),
+                });
+                continue;
+            }
+        }
+        let (base, exponent) = match base {
+            // (x^a)^n = x^(na) nur für ganzzahlige n oder x >= 0
+            Expr::BinaryOp(BinaryOpKind::Pow, inner, symbolic) if exponent.fract() == 0.0 || sign(&inner).is_nonnegative() => {
+                (*inner, Expr::BinaryOp(BinaryOpKind::Mul, Box::new(Expr::Number(exponent)), symbolic))
+            }
+            base => (base, Expr::Number(exponent)),
+        };
+        match factors.iter_mut().find(|(other, _)| *other == base) {
+            Some(entry) => entry.1 = Expr::BinaryOp(BinaryOpKind::Add, Box::new(entry.1.clone()), Box::new(exponent)),
+            None => factors.push((base, exponent)),
+        }
+    }
+
+    let mut product: Vec<(Expr, f64)> = factors
+        .into_iter()
+        .map(|(base, exponent)| match collect(exponent) {
+            Expr::Number(n) => (base, n),
+            exponent => (Expr::BinaryOp(BinaryOpKind::Pow, Box::new(base), Box::new(exponent)), 1.0),
+        })
+        .collect();
+    if let Some(argument) = exp_argument {
+        match collect(argument) {
+            Expr::Number(0.0) => {}
+            argument => product.push((Expr::Call(Box::new(Expr::Var("exp".to_string())), vec![argument]), 1.0)),
+        }
+    }
+    (coefficient, build_product(product))
+}
+
+/// Setzt ein Produkt aus `(Basis, Exponent)`-Paaren zusammen.
+/// Faktoren mit negativem Exponenten landen im Nenner, solche mit Exponent 0 entfallen.
+pub fn build_product(factors: Vec<(Expr, f64)>) -> Expr {
+    let power = |base: Expr, exponent: f64| {
+        if exponent == 1.0 {
+            base
+        } else {
+            Expr::BinaryOp(BinaryOpKind::Pow, Box::new(base), Box::new(Expr::Number(exponent)))
+        }
+    };
+    let mut numerator = Expr::Number(1.0);
+    let mut denominator = Expr::Number(1.0);
+    for (base, exponent) in factors {
+        if exponent > 0.0 {
+            numerator = multiply(numerator, power(base, exponent));
+        } else if exponent < 0.0 {
+            denominator = multiply(denominator, power(base, -exponent));
+        }
+    }
+    if denominator == Expr::Number(1.0) {
+        numerator
+    } else {
+        Expr::BinaryOp(BinaryOpKind::Div, Box::new(numerator), Box::new(denominator))
+    }
+}
+
+/// Zerlegt ein Produkt in Basen mit Zahlenexponenten, Divisionen ergeben negative Exponenten.
+/// `sign` ist 1 für den Zähler und -1 für den Nenner.
+pub fn flatten_factors(expr: &Expr, sign: f64) -> Vec<(Expr, f64)> {
+    match expr {
+        Expr::BinaryOp(BinaryOpKind::Mul, left, right) => {
+            let mut factors = flatten_factors(left, sign);
+            factors.extend(flatten_factors(right, sign));
+            factors
+        }
+        Expr::BinaryOp(BinaryOpKind::Div, left, right) => {
+            let mut factors = flatten_factors(left, sign);
+            factors.extend(flatten_factors(right, -sign));
+            factors
+        }
+        Expr::BinaryOp(BinaryOpKind::Pow, base, exponent) => match **exponent {
+            Expr::Number(n) => vec![((**base).clone(), sign * n)],
+            _ => vec![(expr.clone(), sign)],
         },
-        _ => panic!("Error: Unsupported elementary function: {}", name),
+        Expr::UnaryOp(UnaryOpKind::Neg, inner) => {
+            let mut factors = flatten_factors(inner, sign);
+            factors.push((Expr::Number(-1.0), 1.0));
+            factors
+        }
+        _ => vec![(expr.clone(), sign)],
+    }
+}
+
+// Schlüssel für den Vergleich von Termen, unabhängig von der Reihenfolge der Faktoren
+fn monomial_key(expr: &Expr) -> String {
+    let mut keys: Vec<String> = flatten_factors(expr, 1.0)
+        .into_iter()
+        .map(|(base, exponent)| format!("{:?}^{}", base, exponent))
+        .collect();
+    keys.sort();
+    keys.join("*")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, arg: Expr) -> Expr {
+        Expr::Call(Box::new(Expr::Var(name.to_string())), vec![arg])
+    }
+
+    #[test]
+    fn test_exact_elementary_values() {
+        assert_eq!(simplify(call("sin", Expr::Number(0.0)), false), Expr::Number(0.0));
+        assert_eq!(simplify(call("exp", Expr::Number(0.0)), false), Expr::Number(1.0));
+        assert_eq!(simplify(call("ln", Expr::Number(1.0)), false), Expr::Number(0.0));
+        // Arkuswerte als Vielfache von π
+        let pi = Expr::Var("pi".to_string());
+        let quarter = Expr::BinaryOp(BinaryOpKind::Div, Box::new(pi.clone()), Box::new(Expr::Number(4.0)));
+        assert_eq!(simplify(call("atan", Expr::Number(1.0)), false), quarter);
+        assert_eq!(simplify(call("acos", Expr::Number(-1.0)), false), pi);
+        assert_eq!(simplify(call("atan", Expr::Number(1.0)), true), Expr::Number(std::f64::consts::FRAC_PI_4));
+        // Ohne numerischen Modus bleibt sin(1) exakt stehen
+        assert_eq!(simplify(call("sin", Expr::Number(1.0)), false), call("sin", Expr::Number(1.0)));
+    }
+
+    #[test]
+    fn test_numeric_evaluation() {
+        assert_eq!(simplify(call("sin", Expr::Number(1.0)), true), Expr::Number(1.0f64.sin()));
+        let cos_pi = call("cos", Expr::Var("pi".to_string()));
+        assert_eq!(simplify(cos_pi, true), Expr::Number(-1.0));
+        // Symbolische Argumente führen nicht mehr zu einem Absturz
+        let sin_x = call("sin", Expr::Var("x".to_string()));
+        assert_eq!(simplify(sin_x.clone(), true), sin_x);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_unknown_function_keeps_arguments() {
+        let f = call("f", Expr::BinaryOp(BinaryOpKind::Add, Box::new(Expr::Number(1.0)), Box::new(Expr::Number(2.0))));
+        assert_eq!(simplify(f, true), call("f", Expr::Number(3.0)));
+    }
+
+    #[test]
+    fn test_elementary_identities() {
+        let x = Expr::Var("x".to_string());
+        assert_eq!(simplify(call("exp", call("ln", x.clone())), false), x);
+        assert_eq!(simplify(call("sinh", call("asinh", x.clone())), false), x);
+        assert_eq!(simplify(call("abs", call("abs", x.clone())), false), call("abs", x.clone()));
+        assert_eq!(simplify(call("abs", Expr::UnaryOp(UnaryOpKind::Neg, Box::new(x.clone()))), false), call("abs", x.clone()));
+        assert_eq!(simplify(call("cosh", Expr::Number(0.0)), false), Expr::Number(1.0));
+        assert_eq!(simplify(call("gamma", Expr::Number(5.0)), false), Expr::Number(24.0));
+        // sqrt wird als Potenz dargestellt
+        let sqrt = Expr::BinaryOp(BinaryOpKind::Pow, Box::new(x.clone()), Box::new(Expr::Number(0.5)));
+        assert_eq!(simplify(call("sqrt", x.clone()), false), sqrt);
+        let log = |b: Expr, arg: Expr| Expr::Call(Box::new(Expr::Var("log".to_string())), vec![b, arg]);
+        assert_eq!(simplify(log(x.clone(), x.clone()), false), Expr::Number(1.0));
+        assert_eq!(simplify(log(Expr::Number(2.0), Expr::Number(8.0)), true), Expr::Number(3.0));
+    }
+
+    #[test]
+    fn test_composition() {
+        // Punktfreie Verkettung: sin(id^2)(x) = sin(x^2)
+        let x = Expr::Var("x".to_string());
+        let square = Expr::BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Var("id".to_string())), Box::new(Expr::Number(2.0)));
+        let composed = Expr::Call(Box::new(call("sin", square)), vec![x.clone()]);
+        let expected = call("sin", Expr::BinaryOp(BinaryOpKind::Pow, Box::new(x), Box::new(Expr::Number(2.0))));
+        assert_eq!(simplify(composed, false), expected);
+    }
+}
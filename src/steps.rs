@@ -0,0 +1,166 @@
+use crate::parser::Expr;
+use crate::render::render_latex;
+use crate::simplify::simplify;
+use std::cell::RefCell;
+
+/// Eine Rechenregel, die bei einem Schritt angewendet wurde.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Rule {
+    Constant,
+    Identity,
+    Sum,
+    Difference,
+    Product,
+    Quotient,
+    /// (f^n)' = n f^(n-1) f' bei konstantem Exponenten
+    Power,
+    /// (a^g)' = a^g ln(a) g' bei konstanter Basis
+    Exponential,
+    /// (f^g)' = f^g (g' ln(f) + g f'/f)
+    GeneralPower,
+    /// (-f)' = -f'
+    Negation,
+    /// Ableitung einer elementaren Funktion direkt an der Variablen, z.B. sin(x)
+    Elementary(String),
+    Chain,
+    /// Kettenregel für Funktionen mehrerer Argumente
+    MultivariableChain,
+    Logarithm,
+    Componentwise,
+}
+
+impl Rule {
+    /// Name der Regel für die Anzeige im LaTeX-Textmodus.
+    pub fn description(&self) -> String {
+        match self {
+            Rule::Constant => "Constant rule".to_string(),
+            Rule::Identity => "Identity rule".to_string(),
+            Rule::Sum => "Sum rule".to_string(),
+            Rule::Difference => "Difference rule".to_string(),
+            Rule::Product => "Product rule".to_string(),
+            Rule::Quotient => "Quotient rule".to_string(),
+            Rule::Power => "Power rule".to_string(),
+            Rule::Exponential => "Exponential rule".to_string(),
+            Rule::GeneralPower => "General power rule".to_string(),
+            Rule::Negation => "Negation rule".to_string(),
+            Rule::Elementary(name) => format!("Derivative of {}", name),
+            Rule::Chain => "Chain rule".to_string(),
+            Rule::MultivariableChain => "Multivariable chain rule".to_string(),
+            Rule::Logarithm => "Change of base".to_string(),
+            Rule::Componentwise => "Componentwise".to_string(),
+        }
+    }
+}
+
+/// Ein Schritt der Rechnung: `operator(input) = output` nach der Regel `rule`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Step {
+    pub rule: Rule,
+    /// LaTeX des Operators, z.B. `\frac{d}{dx}`
+    pub operator: String,
+    pub input: Expr,
+    pub output: Option<Expr>,
+}
+
+thread_local! {
+    // Wie die Annahmen ein globaler Kontext, damit die Rechenfunktionen ihre Signatur behalten.
+    // `None`, solange keine Aufzeichnung läuft.
+    static TRACE: RefCell<Option<Vec<Step>>> = const { RefCell::new(None) };
+}
+
+/// Führt `f` aus und zeichnet dabei alle Schritte auf.
+pub fn traced<T>(f: impl FnOnce() -> T) -> (T, Vec<Step>) {
+    let outer = TRACE.with(|trace| trace.borrow_mut().replace(vec![]));
+    let result = f();
+    let steps = TRACE.with(|trace| std::mem::replace(&mut *trace.borrow_mut(), outer)).unwrap_or_default();
+    (result, steps)
+}
+
+/// Beginnt einen Schritt, falls aufgezeichnet wird. Die Schritte stehen in der Reihenfolge,
+/// in der die Regeln angewendet werden, also die äußere Regel vor den inneren.
+pub fn begin(operator: impl FnOnce() -> String, input: &Expr) -> Option<usize> {
+    TRACE.with(|trace| {
+        let mut trace = trace.borrow_mut();
+        let steps = trace.as_mut()?;
+        // Die Regel steht erst fest, wenn der Schritt ausgeführt ist, und wird in `finish` gesetzt
+        steps.push(Step { rule: Rule::Constant, operator: operator(), input: input.clone(), output: None });
+        Some(steps.len() - 1)
+    })
+}
+
+/// Schließt einen Schritt mit der tatsächlich angewendeten Regel und dem (vereinfachten) Ergebnis ab.
+pub fn finish(step: Option<usize>, rule: Rule, output: &Expr) {
+    let Some(index) = step else {
+        return;
+    };
+    let output = simplify(output.clone(), false);
+    TRACE.with(|trace| {
+        if let Some(step) = trace.borrow_mut().as_mut().and_then(|steps| steps.get_mut(index)) {
+            step.rule = rule;
+            step.output = Some(output);
+        }
+    });
+}
+
+/// Rendert die Schritte als `aligned`-Umgebung mit einer Zeile pro Schritt.
+pub fn render_steps(steps: &[Step]) -> String {
+    let lines = steps
+        .iter()
+        .map(|step| {
+            let output = step.output.as_ref().map(render_latex).unwrap_or_default();
+            format!(
+                "&\\text{{{}:}}\\quad {}\\left({}\\right)={}",
+                step.rule.description(),
+                step.operator,
+                render_latex(&step.input),
+                output
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" \\\\ ");
+    format!("\\begin{{aligned}}{}\\end{{aligned}}", lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differentiate::differentiate;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_differentiation_trace() {
+        let expr = parse("x^2 * sin(3*x)");
+        let (result, steps) = traced(|| differentiate(expr.clone(), "x".to_string()));
+        assert_eq!(result, differentiate(expr, "x".to_string()));
+        let rules: Vec<Rule> = steps.iter().map(|step| step.rule.clone()).collect();
+        assert_eq!(rules, vec![
+            Rule::Product,
+            Rule::Power,
+            Rule::Identity,
+            Rule::Chain,
+            Rule::Product,
+            Rule::Constant,
+            Rule::Identity,
+        ]);
+        assert_eq!(steps[0].output.as_ref().map(render_latex), Some(render_latex(&simplify(result, false))));
+        assert_eq!(
+            render_steps(&steps[1..2]),
+            "\\begin{aligned}&\\text{Power rule:}\\quad \\frac{d}{dx}\\left(x^{2}\\right)=2 \\cdot x\\end{aligned}"
+        );
+    }
+
+    #[test]
+    fn test_negation_and_power_rules() {
+        let (_, steps) = traced(|| differentiate(parse("-(2^x)"), "x".to_string()));
+        let rules: Vec<Rule> = steps.iter().map(|step| step.rule.clone()).collect();
+        assert_eq!(rules, vec![Rule::Negation, Rule::Exponential, Rule::Constant, Rule::Identity]);
+        assert_eq!(Rule::Negation.description(), "Negation rule");
+    }
+
+    #[test]
+    fn test_no_trace_outside_traced() {
+        assert_eq!(begin(String::new, &Expr::Number(1.0)), None);
+        let (_, steps) = traced(|| ());
+        assert!(steps.is_empty());
+    }
+}
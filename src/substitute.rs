@@ -22,7 +22,16 @@ pub fn substitute (expr: Expr, var: String, value: Expr) -> Expr {
         Expr::UnaryOp(op, expr) => Expr::UnaryOp(op, Box::new(substitute(*expr, var, value))),
         // Wenn die Expression ein Funktionsaufruf ist, wird die Substitution rekursiv auf die Argumente angewendet
         Expr::Call(expr, args) => Expr::Call(expr, args.into_iter().map(|arg| substitute(arg, var.clone(), value.clone())).collect()),
-        // Für alle anderen Fälle wird ein Panic ausgelöst
-        _ => panic!("Not implemented"),
+        // Unausgewertete Ableitungen sind Funktionen und enthalten keine Variablen
+        Expr::Derivative(_, _) => expr,
+        Expr::Matrix(rows) => Expr::Matrix(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(|entry| substitute(entry, var.clone(), value.clone())).collect())
+                .collect(),
+        ),
+        Expr::Equation(left, right) => Expr::Equation(
+            Box::new(substitute(*left, var.clone(), value.clone())),
+            Box::new(substitute(*right, var, value)),
+        ),
     }
 }
\ No newline at end of file
@@ -0,0 +1,471 @@
+use crate::assumptions::{is_even, is_integer, is_odd};
+use crate::complexity::{complexity, Measure};
+use crate::parser::{BinaryOpKind, Expr, UnaryOpKind};
+use crate::polynomial::exact_number;
+use crate::simplify::{build_product, build_sum, collect, expand, flatten_factors, simplify, split_coefficient, terms};
+
+/// Vereinfacht trigonometrische Ausdrücke mit Pythagoras, Doppelwinkel-, Summen-zu-Produkt-,
+/// Symmetrie- und exakten Werten, z.B. `sin(x)^2 + cos(x)^2 = 1` oder `sin(pi/6) = 1/2`.
+pub fn trigsimp(expr: Expr) -> Expr {
+    let mut current = collect(simplify(expr, false));
+    // Die Regeln werden so lange angewendet, bis sich der Ausdruck nicht mehr ändert
+    for _ in 0..32 {
+        let next = collect(trig_rules(current.clone()));
+        if next == current {
+            break;
+        }
+        current = next;
+    }
+    current
+}
+
+/// Wendet die Additionstheoreme an, z.B. `sin(2x) = 2 sin(x) cos(x)` und
+/// `cos(a+b) = cos(a) cos(b) - sin(a) sin(b)`.
+pub fn trigexpand(expr: Expr) -> Expr {
+    fn expand_calls(expr: Expr) -> Expr {
+        match expr {
+            Expr::BinaryOp(op, left, right) => {
+                Expr::BinaryOp(op, Box::new(expand_calls(*left)), Box::new(expand_calls(*right)))
+            }
+            Expr::UnaryOp(op, inner) => Expr::UnaryOp(op, Box::new(expand_calls(*inner))),
+            Expr::Call(func, args) => {
+                let args: Vec<Expr> = args.into_iter().map(expand_calls).collect();
+                match (*func, args.len()) {
+                    (Expr::Var(name), 1) if is_trig(&name) => expand_trig_call(&name, args[0].clone()),
+                    (func, _) => Expr::Call(Box::new(func), args),
+                }
+            }
+            _ => expr,
+        }
+    }
+    normalize_calls(expand(expand_calls(simplify(expr, false))))
+}
+
+/// Ersetzt Potenzen und Produkte von Sinus und Kosinus durch Summen mit Vielfachen des Winkels,
+/// z.B. `sin(x)^2 = (1 - cos(2x))/2` und `sin(x) cos(x) = sin(2x)/2`.
+pub fn trigreduce(expr: Expr) -> Expr {
+    let mut current = expand(simplify(expr, false));
+    for _ in 0..64 {
+        let next = expand(reduce_products(current.clone()));
+        if next == current {
+            break;
+        }
+        current = next;
+    }
+    exact_constant_term(normalize_calls(current))
+}
+
+// Schreibt den konstanten Summanden wie die übrigen Koeffizienten als Bruch, z.B. `1/2 - cos(2x)/2`
+fn exact_constant_term(expr: Expr) -> Expr {
+    match expr {
+        Expr::Number(n) => exact_number(n),
+        Expr::BinaryOp(op @ (BinaryOpKind::Add | BinaryOpKind::Sub), left, right) => {
+            Expr::BinaryOp(op, Box::new(exact_constant_term(*left)), right)
+        }
+        _ => expr,
+    }
+}
+
+// Prüft, ob der Name eine trigonometrische Funktion ist, die hier behandelt wird
+fn is_trig(name: &str) -> bool {
+    name == "sin" || name == "cos" || name == "tan"
+}
+
+fn call(name: &str, arg: Expr) -> Expr {
+    Expr::Call(Box::new(Expr::Var(name.to_string())), vec![arg])
+}
+
+// Erkennt sin(u), cos(u) und tan(u)
+fn as_trig(expr: &Expr) -> Option<(&str, &Expr)> {
+    if let Expr::Call(func, args) = expr {
+        if let Expr::Var(name) = &**func {
+            if is_trig(name) && args.len() == 1 {
+                return Some((name.as_str(), &args[0]));
+            }
+        }
+    }
+    None
+}
+
+// Erkennt f(u)^n, wobei f eine trigonometrische Funktion ist und n eine Zahl
+fn as_trig_power(expr: &Expr) -> Option<(&str, &Expr, f64)> {
+    match expr {
+        Expr::BinaryOp(BinaryOpKind::Pow, base, exponent) => match (as_trig(base), &**exponent) {
+            (Some((name, arg)), Expr::Number(n)) => Some((name, arg, *n)),
+            _ => None,
+        },
+        _ => as_trig(expr).map(|(name, arg)| (name, arg, 1.0)),
+    }
+}
+
+fn trig_power(name: &str, arg: Expr, n: f64) -> Expr {
+    build_product(vec![(call(name, arg), n)])
+}
+
+// Berechnet die Linearkombination a * left + b * right, z.B. für (x+y)/2
+fn combine(a: f64, left: &Expr, b: f64, right: &Expr) -> Expr {
+    let mut combined: Vec<(f64, Expr)> = terms(left).into_iter().map(|(c, term)| (a * c, term)).collect();
+    combined.extend(terms(right).into_iter().map(|(c, term)| (b * c, term)));
+    collect(build_sum(combined))
+}
+
+// Gibt q zurück, falls das Argument q * pi ist
+fn pi_multiple(arg: &Expr) -> Option<f64> {
+    let arg_terms = terms(&collect(arg.clone()));
+    match arg_terms.as_slice() {
+        [(c, Expr::Number(n))] => (*c * n == 0.0).then_some(0.0),
+        [(c, Expr::Var(name))] if name == "pi" => Some(*c),
+        _ => None,
+    }
+}
+
+// Exakte Werte an Vielfachen von pi/6 und pi/4
+fn exact_value(name: &str, q: f64) -> Option<Expr> {
+    let twelfths = q * 12.0;
+    if (twelfths - twelfths.round()).abs() > 1e-9 {
+        return None;
+    }
+    let k = (twelfths.round() as i64).rem_euclid(24);
+    if k % 2 != 0 && k % 3 != 0 {
+        return None;
+    }
+    let angle = k as f64 * std::f64::consts::PI / 12.0;
+    let value = match name {
+        "sin" => angle.sin(),
+        "cos" => angle.cos(),
+        "tan" if angle.cos().abs() > 1e-12 => angle.tan(),
+        _ => return None,
+    };
+    let sqrt = |n: f64| Expr::BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Number(n)), Box::new(Expr::Number(0.5)));
+    let div = |a: Expr, b: f64| Expr::BinaryOp(BinaryOpKind::Div, Box::new(a), Box::new(Expr::Number(b)));
+    let magnitude = value.abs();
+    let candidates = [
+        (0.0, Expr::Number(0.0)),
+        (0.5, Expr::Number(0.5)),
+        (1.0, Expr::Number(1.0)),
+        (2f64.sqrt() / 2.0, div(sqrt(2.0), 2.0)),
+        (3f64.sqrt() / 2.0, div(sqrt(3.0), 2.0)),
+        (3f64.sqrt() / 3.0, div(sqrt(3.0), 3.0)),
+        (3f64.sqrt(), sqrt(3.0)),
+    ];
+    let (_, exact) = candidates.into_iter().find(|(candidate, _)| (candidate - magnitude).abs() < 1e-9)?;
+    Some(match exact {
+        Expr::Number(n) if value < 0.0 => Expr::Number(-n),
+        exact if value < 0.0 => Expr::UnaryOp(UnaryOpKind::Neg, Box::new(exact)),
+        exact => exact,
+    })
+}
+
+// Symmetrie und exakte Werte für einen einzelnen Aufruf: cos(-x) = cos(x), sin(-x) = -sin(x), sin(pi/6) = 1/2
+fn simplify_trig_call(name: &str, arg: Expr) -> Expr {
+    if let Some(q) = pi_multiple(&arg) {
+        if let Some(value) = exact_value(name, q) {
+            return value;
+        }
+    }
+    // Ganzzahlige Vielfache n*pi laut Annahmen: sin(n pi) = 0, cos(n pi) = (-1)^n
+    let multiple = collect(Expr::BinaryOp(BinaryOpKind::Div, Box::new(arg.clone()), Box::new(Expr::Var("pi".to_string()))));
+    if !matches!(multiple, Expr::Number(_)) && is_integer(&multiple) {
+        match name {
+            "sin" | "tan" => return Expr::Number(0.0),
+            "cos" if is_even(&multiple) => return Expr::Number(1.0),
+            "cos" if is_odd(&multiple) => return Expr::Number(-1.0),
+            "cos" => return Expr::BinaryOp(BinaryOpKind::Pow, Box::new(Expr::Number(-1.0)), Box::new(multiple)),
+            _ => {}
+        }
+    }
+    let arg_terms = terms(&arg);
+    if arg_terms.first().is_some_and(|(c, _)| *c < 0.0) {
+        let negated = build_sum(arg_terms.into_iter().map(|(c, term)| (-c, term)).collect());
+        return match name {
+            "cos" => call(name, negated),
+            _ => Expr::UnaryOp(UnaryOpKind::Neg, Box::new(call(name, negated))),
+        };
+    }
+    call(name, arg)
+}
+
+// Wendet `simplify_trig_call` auf alle trigonometrischen Aufrufe an
+fn normalize_calls(expr: Expr) -> Expr {
+    let result = match expr {
+        Expr::BinaryOp(op, left, right) => {
+            Expr::BinaryOp(op, Box::new(normalize_calls(*left)), Box::new(normalize_calls(*right)))
+        }
+        Expr::UnaryOp(op, inner) => Expr::UnaryOp(op, Box::new(normalize_calls(*inner))),
+        Expr::Call(func, args) => {
+            let args: Vec<Expr> = args.into_iter().map(normalize_calls).collect();
+            match (*func, args.len()) {
+                (Expr::Var(name), 1) if is_trig(&name) => simplify_trig_call(&name, args[0].clone()),
+                (func, _) => Expr::Call(Box::new(func), args),
+            }
+        }
+        _ => expr,
+    };
+    collect(result)
+}
+
+// Ein Durchlauf aller Vereinfachungsregeln von innen nach außen
+fn trig_rules(expr: Expr) -> Expr {
+    match expr {
+        Expr::Call(func, args) => {
+            let args: Vec<Expr> = args.into_iter().map(trig_rules).collect();
+            match (*func, args.len()) {
+                (Expr::Var(name), 1) if is_trig(&name) => simplify_trig_call(&name, args[0].clone()),
+                (func, _) => Expr::Call(Box::new(func), args),
+            }
+        }
+        Expr::BinaryOp(op @ (BinaryOpKind::Add | BinaryOpKind::Sub), left, right) => {
+            let expr = Expr::BinaryOp(op, Box::new(trig_rules(*left)), Box::new(trig_rules(*right)));
+            simplify_trig_sum(terms(&expr))
+        }
+        Expr::BinaryOp(op @ (BinaryOpKind::Mul | BinaryOpKind::Div), left, right) => {
+            simplify_trig_product(Expr::BinaryOp(op, Box::new(trig_rules(*left)), Box::new(trig_rules(*right))))
+        }
+        Expr::BinaryOp(op, left, right) => Expr::BinaryOp(op, Box::new(trig_rules(*left)), Box::new(trig_rules(*right))),
+        Expr::UnaryOp(op, inner) => Expr::UnaryOp(op, Box::new(trig_rules(*inner))),
+        _ => expr,
+    }
+}
+
+// Regeln für Summen: Pythagoras, cos^2 - sin^2 = cos(2u) und Summen zu Produkten
+fn simplify_trig_sum(mut sum: Vec<(f64, Expr)>) -> Expr {
+    'search: loop {
+        for i in 0..sum.len() {
+            for j in 0..sum.len() {
+                if i == j {
+                    continue;
+                }
+                if let Some(replacement) = combine_trig_terms(&sum[i], &sum[j]) {
+                    let (first, second) = (i.max(j), i.min(j));
+                    sum.remove(first);
+                    sum.remove(second);
+                    sum.extend(replacement);
+                    continue 'search;
+                }
+            }
+        }
+        return build_sum(sum);
+    }
+}
+
+// Versucht, zwei Summanden zu einem einfacheren Ausdruck zusammenzufassen
+fn combine_trig_terms((a, left): &(f64, Expr), (b, right): &(f64, Expr)) -> Option<Vec<(f64, Expr)>> {
+    match (as_trig_power(left), as_trig_power(right)) {
+        (Some(("sin", u, 2.0)), Some(("cos", v, 2.0))) if u == v => {
+            if a == b {
+                // a sin(u)^2 + a cos(u)^2 = a
+                return Some(vec![(*a, Expr::Number(1.0))]);
+            }
+            if *a == -b {
+                // b cos(u)^2 - b sin(u)^2 = b cos(2u)
+                return Some(vec![(*b, call("cos", combine(2.0, u, 0.0, u)))]);
+            }
+            None
+        }
+        (None, Some((name @ ("sin" | "cos"), u, 2.0))) if *left == Expr::Number(1.0) && *a == -b => {
+            // a - a sin(u)^2 = a cos(u)^2 und a - a cos(u)^2 = a sin(u)^2
+            let other = if name == "sin" { "cos" } else { "sin" };
+            Some(vec![(*a, trig_power(other, u.clone(), 2.0))])
+        }
+        (Some((name @ ("sin" | "cos"), u, 1.0)), Some((other, v, 1.0))) if name == other && a.abs() == b.abs() && u != v => {
+            // Summen zu Produkten, nur wenn das Ergebnis kürzer ist
+            let half_sum = combine(0.5, u, 0.5, v);
+            let half_difference = combine(0.5, u, -0.5, v);
+            let (factor, first, second) = match (name, a == b) {
+                ("sin", true) => (2.0, call("sin", half_sum), call("cos", half_difference)),
+                ("sin", false) => (2.0, call("cos", half_sum), call("sin", half_difference)),
+                ("cos", true) => (2.0, call("cos", half_sum), call("cos", half_difference)),
+                _ => (-2.0, call("sin", half_sum), call("sin", half_difference)),
+            };
+            let product = Expr::BinaryOp(BinaryOpKind::Mul, Box::new(first), Box::new(second));
+            let original = complexity(left, Measure::NodeCount) + complexity(right, Measure::NodeCount);
+            (complexity(&product, Measure::NodeCount) < original).then(|| vec![(a * factor, product)])
+        }
+        _ => None,
+    }
+}
+
+// Regeln für Produkte: tan(u) cos(u) = sin(u), sin(u)/cos(u) = tan(u) und 2 sin(u) cos(u) = sin(2u)
+fn simplify_trig_product(expr: Expr) -> Expr {
+    let (coefficient, rest) = split_coefficient(&expr);
+    let mut factors: Vec<(Expr, f64)> = vec![];
+    for (base, exponent) in flatten_factors(&rest, 1.0) {
+        match factors.iter_mut().find(|(other, _)| *other == base) {
+            Some(entry) => entry.1 += exponent,
+            None => factors.push((base, exponent)),
+        }
+    }
+    let mut coefficient = coefficient;
+
+    let position = |factors: &Vec<(Expr, f64)>, name: &str, arg: &Expr| {
+        factors.iter().position(|(base, exponent)| *exponent != 0.0 && as_trig(base) == Some((name, arg)))
+    };
+    let args: Vec<Expr> = factors.iter().filter_map(|(base, _)| as_trig(base).map(|(_, arg)| arg.clone())).collect();
+    for arg in args {
+        // tan(u)^n cos(u)^m = sin(u)^k tan(u)^(n-k) cos(u)^(m-k) mit k = min(n, m)
+        if let (Some(t), Some(c)) = (position(&factors, "tan", &arg), position(&factors, "cos", &arg)) {
+            if factors[t].1 > 0.0 && factors[c].1 > 0.0 {
+                let k = factors[t].1.min(factors[c].1);
+                factors[t].1 -= k;
+                factors[c].1 -= k;
+                factors.push((call("sin", arg.clone()), k));
+            }
+        }
+        // sin(u)^n / cos(u)^n = tan(u)^n
+        if let (Some(s), Some(c)) = (position(&factors, "sin", &arg), position(&factors, "cos", &arg)) {
+            if factors[s].1 > 0.0 && factors[c].1 < 0.0 {
+                let k = factors[s].1.min(-factors[c].1);
+                factors[s].1 -= k;
+                factors[c].1 += k;
+                factors.push((call("tan", arg.clone()), k));
+            }
+        }
+        // 2 sin(u) cos(u) = sin(2u)
+        if let (Some(s), Some(c)) = (position(&factors, "sin", &arg), position(&factors, "cos", &arg)) {
+            if factors[s].1 == 1.0 && factors[c].1 == 1.0 && (coefficient / 2.0).fract() == 0.0 {
+                factors[s].1 = 0.0;
+                factors[c].1 = 0.0;
+                coefficient /= 2.0;
+                factors.push((call("sin", combine(2.0, &arg, 0.0, &arg)), 1.0));
+            }
+        }
+    }
+    build_sum(vec![(coefficient, build_product(factors))])
+}
+
+// Additionstheoreme für einen einzelnen Aufruf, rekursiv für Summen und ganzzahlige Vielfache
+fn expand_trig_call(name: &str, arg: Expr) -> Expr {
+    let arg_terms = terms(&collect(arg.clone()));
+    let (a, b) = match arg_terms.as_slice() {
+        [first, rest @ ..] if !rest.is_empty() => (build_sum(vec![first.clone()]), build_sum(rest.to_vec())),
+        // Vielfache n*u werden als (n-1)*u + u geschrieben
+        [(c, u)] if c.fract() == 0.0 && c.abs() >= 2.0 && *u != Expr::Number(1.0) => {
+            if *c < 0.0 {
+                let expanded = expand_trig_call(name, build_sum(vec![(-c, u.clone())]));
+                return match name {
+                    "cos" => expanded,
+                    _ => Expr::UnaryOp(UnaryOpKind::Neg, Box::new(expanded)),
+                };
+            }
+            (build_sum(vec![(c - 1.0, u.clone())]), u.clone())
+        }
+        _ => return call(name, arg),
+    };
+    let sin_a = || expand_trig_call("sin", a.clone());
+    let cos_a = || expand_trig_call("cos", a.clone());
+    let sin_b = || expand_trig_call("sin", b.clone());
+    let cos_b = || expand_trig_call("cos", b.clone());
+    let mul = |l: Expr, r: Expr| Expr::BinaryOp(BinaryOpKind::Mul, Box::new(l), Box::new(r));
+    match name {
+        // sin(a+b) = sin(a) cos(b) + cos(a) sin(b)
+        "sin" => Expr::BinaryOp(BinaryOpKind::Add, Box::new(mul(sin_a(), cos_b())), Box::new(mul(cos_a(), sin_b()))),
+        // cos(a+b) = cos(a) cos(b) - sin(a) sin(b)
+        "cos" => Expr::BinaryOp(BinaryOpKind::Sub, Box::new(mul(cos_a(), cos_b())), Box::new(mul(sin_a(), sin_b()))),
+        // tan(a+b) = (tan(a) + tan(b)) / (1 - tan(a) tan(b))
+        _ => {
+            let tan_a = expand_trig_call("tan", a.clone());
+            let tan_b = expand_trig_call("tan", b.clone());
+            Expr::BinaryOp(
+                BinaryOpKind::Div,
+                Box::new(Expr::BinaryOp(BinaryOpKind::Add, Box::new(tan_a.clone()), Box::new(tan_b.clone()))),
+                Box::new(Expr::BinaryOp(BinaryOpKind::Sub, Box::new(Expr::Number(1.0)), Box::new(mul(tan_a, tan_b)))),
+            )
+        }
+    }
+}
+
+// Ersetzt in jedem Summanden eine Potenz oder ein Produkt von sin/cos durch eine Summe
+fn reduce_products(expr: Expr) -> Expr {
+    let expr = match expr {
+        Expr::Call(func, args) => Expr::Call(func, args.into_iter().map(reduce_products).collect()),
+        _ => expr,
+    };
+    let reduced = terms(&expr)
+        .into_iter()
+        .map(|(coefficient, term)| (coefficient, reduce_term(term)))
+        .collect();
+    build_sum(reduced)
+}
+
+fn reduce_term(term: Expr) -> Expr {
+    let mut factors = flatten_factors(&term, 1.0);
+    let is_reducible = |(base, exponent): &(Expr, f64)| {
+        matches!(as_trig(base), Some(("sin" | "cos", _))) && *exponent >= 1.0 && exponent.fract() == 0.0
+    };
+
+    let replacement = if let Some(i) = factors.iter().position(|factor| is_reducible(factor) && factor.1 >= 2.0) {
+        // sin(u)^2 = (1 - cos(2u))/2 und cos(u)^2 = (1 + cos(2u))/2
+        let (name, u) = as_trig(&factors[i].0).map(|(name, u)| (name.to_string(), u.clone())).unwrap();
+        factors[i].1 -= 2.0;
+        let sign = if name == "sin" { -1.0 } else { 1.0 };
+        build_sum(vec![(0.5, Expr::Number(1.0)), (0.5 * sign, call("cos", combine(2.0, &u, 0.0, &u)))])
+    } else {
+        let positions: Vec<usize> = (0..factors.len()).filter(|i| is_reducible(&factors[*i])).collect();
+        if positions.len() < 2 {
+            return term;
+        }
+        let (first, u) = as_trig(&factors[positions[0]].0).map(|(name, u)| (name.to_string(), u.clone())).unwrap();
+        let (second, v) = as_trig(&factors[positions[1]].0).map(|(name, v)| (name.to_string(), v.clone())).unwrap();
+        factors[positions[0]].1 -= 1.0;
+        factors[positions[1]].1 -= 1.0;
+        let sum = combine(1.0, &u, 1.0, &v);
+        let difference = combine(1.0, &u, -1.0, &v);
+        match (first.as_str(), second.as_str()) {
+            // sin(u) sin(v) = (cos(u-v) - cos(u+v))/2
+            ("sin", "sin") => build_sum(vec![(0.5, call("cos", difference)), (-0.5, call("cos", sum))]),
+            // cos(u) cos(v) = (cos(u-v) + cos(u+v))/2
+            ("cos", "cos") => build_sum(vec![(0.5, call("cos", difference)), (0.5, call("cos", sum))]),
+            // sin(u) cos(v) = (sin(u+v) + sin(u-v))/2
+            ("sin", _) => build_sum(vec![(0.5, call("sin", sum)), (0.5, call("sin", difference))]),
+            // cos(u) sin(v) = (sin(u+v) - sin(u-v))/2
+            _ => build_sum(vec![(0.5, call("sin", sum)), (-0.5, call("sin", difference))]),
+        }
+    };
+    factors.push((replacement, 1.0));
+    build_product(factors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use crate::render::render_latex;
+
+    #[test]
+    fn test_trigsimp() {
+        assert_eq!(trigsimp(parse("sin(x)^2 + cos(x)^2")), Expr::Number(1.0));
+        assert_eq!(render_latex(&trigsimp(parse("tan(x)*cos(x)"))), "\\sin\\left(x\\right)");
+        assert_eq!(render_latex(&trigsimp(parse("cos(-x)"))), "\\cos\\left(x\\right)");
+        assert_eq!(render_latex(&trigsimp(parse("2*sin(x)*cos(x)"))), "\\sin\\left(2 \\cdot x\\right)");
+        assert_eq!(trigsimp(parse("sin(pi/6)")), Expr::Number(0.5));
+        assert_eq!(render_latex(&trigsimp(parse("cos(pi/6)"))), "\\frac{\\sqrt{3}}{2}");
+    }
+
+    #[test]
+    fn test_trigsimp_integer_multiple() {
+        use crate::assumptions::{assume_relation, forget_all};
+        // Ohne Annahme über n bleibt sin(n pi) stehen
+        assert_eq!(render_latex(&trigsimp(parse("sin(n*pi)"))), "\\sin\\left(n \\cdot \\pi\\right)");
+        assume_relation("n ∈ ℤ").unwrap();
+        assert_eq!(trigsimp(parse("sin(n*pi)")), Expr::Number(0.0));
+        assert_eq!(trigsimp(parse("cos(2*n*pi)")), Expr::Number(1.0));
+        forget_all();
+    }
+
+    #[test]
+    fn test_trigexpand() {
+        assert_eq!(
+            render_latex(&trigexpand(parse("sin(2*x)"))),
+            "2 \\cdot \\sin\\left(x\\right) \\cdot \\cos\\left(x\\right)"
+        );
+    }
+
+    #[test]
+    fn test_trigreduce() {
+        assert_eq!(render_latex(&trigreduce(parse("sin(x)^2"))), "\\frac{1}{2}-\\frac{\\cos\\left(2 \\cdot x\\right)}{2}");
+        assert_eq!(
+            render_latex(&trigreduce(parse("sin(x)^4"))),
+            "\\frac{3}{8}-\\frac{\\cos\\left(2 \\cdot x\\right)}{2}+\\frac{\\cos\\left(4 \\cdot x\\right)}{8}"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::differentiate::differentiate_n;
+use crate::parser::{BinaryOpKind, Expr};
+use crate::simplify::{collect, simplify};
+
+/// Gradient ∇f als Spaltenvektor der partiellen Ableitungen nach `vars`.
+pub fn gradient(f: &Expr, vars: &[String]) -> Expr {
+    column(vars.iter().map(|var| partial(f, &[var])).collect())
+}
+
+/// Jacobi-Matrix J_ij = ∂f_i/∂x_j, eine Zeile pro Komponente.
+pub fn jacobian(fs: &[Expr], vars: &[String]) -> Expr {
+    Expr::Matrix(fs.iter().map(|f| vars.iter().map(|var| partial(f, &[var])).collect()).collect())
+}
+
+/// Hesse-Matrix H_ij = ∂²f/∂x_i∂x_j.
+pub fn hessian(f: &Expr, vars: &[String]) -> Expr {
+    Expr::Matrix(vars.iter().map(|x| vars.iter().map(|y| partial(f, &[x, y])).collect()).collect())
+}
+
+/// Divergenz ∇·F = Σ ∂F_i/∂x_i eines Vektorfelds mit so vielen Komponenten wie Variablen.
+pub fn divergence(fs: &[Expr], vars: &[String]) -> Result<Expr, String> {
+    check_dimension(fs, vars)?;
+    let terms = fs.iter().zip(vars).map(|(f, var)| partial(f, &[var]));
+    Ok(sum(terms.collect()))
+}
+
+/// Rotation ∇×F eines Vektorfelds im dreidimensionalen Raum.
+pub fn curl(fs: &[Expr], vars: &[String]) -> Result<Expr, String> {
+    check_dimension(fs, vars)?;
+    let ([p, q, r], [x, y, z]) = (fs, vars) else {
+        return Err("Error: The curl is only defined in three dimensions".to_string());
+    };
+    let component = |a: Expr, b: Expr| collect(simplify(Expr::BinaryOp(BinaryOpKind::Sub, Box::new(a), Box::new(b)), false));
+    Ok(column(vec![
+        component(partial(r, &[y]), partial(q, &[z])),
+        component(partial(p, &[z]), partial(r, &[x])),
+        component(partial(q, &[x]), partial(p, &[y])),
+    ]))
+}
+
+/// Laplace-Operator Δf = Σ ∂²f/∂x_i².
+pub fn laplacian(f: &Expr, vars: &[String]) -> Expr {
+    sum(vars.iter().map(|var| partial(f, &[var, var])).collect())
+}
+
+// Partielle Ableitung nach den Variablen in der gegebenen Reihenfolge
+fn partial(f: &Expr, vars: &[&String]) -> Expr {
+    let orders: Vec<(String, usize)> = vars.iter().map(|var| ((*var).clone(), 1)).collect();
+    differentiate_n(f.clone(), &orders)
+}
+
+fn column(entries: Vec<Expr>) -> Expr {
+    Expr::Matrix(entries.into_iter().map(|entry| vec![entry]).collect())
+}
+
+fn sum(terms: Vec<Expr>) -> Expr {
+    let sum = terms
+        .into_iter()
+        .reduce(|acc, term| Expr::BinaryOp(BinaryOpKind::Add, Box::new(acc), Box::new(term)))
+        .unwrap_or(Expr::Number(0.0));
+    collect(simplify(sum, false))
+}
+
+fn check_dimension(fs: &[Expr], vars: &[String]) -> Result<(), String> {
+    if fs.len() != vars.len() {
+        return Err(format!("Error: Expected {} components, got {}", vars.len(), fs.len()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use crate::render::render_latex;
+
+    fn vars(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_gradient_and_hessian() {
+        let f = parse("x^2*y + y^3");
+        let xy = vars(&["x", "y"]);
+        assert_eq!(
+            render_latex(&gradient(&f, &xy)),
+            "\\begin{pmatrix}2 \\cdot x \\cdot y \\\\ x^{2}+3 \\cdot y^{2}\\end{pmatrix}"
+        );
+        assert_eq!(
+            render_latex(&hessian(&f, &xy)),
+            "\\begin{pmatrix}2 \\cdot y & 2 \\cdot x \\\\ 2 \\cdot x & 6 \\cdot y\\end{pmatrix}"
+        );
+        assert_eq!(render_latex(&laplacian(&f, &xy)), "8 \\cdot y");
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let fs = [parse("x*y"), parse("x + y")];
+        assert_eq!(jacobian(&fs, &vars(&["x", "y"])), Expr::Matrix(vec![
+            vec![Expr::Var("y".to_string()), Expr::Var("x".to_string())],
+            vec![Expr::Number(1.0), Expr::Number(1.0)],
+        ]));
+    }
+
+    #[test]
+    fn test_divergence_and_curl() {
+        let xyz = vars(&["x", "y", "z"]);
+        // F = (-y, x, 0) hat Divergenz 0 und Rotation (0, 0, 2)
+        let field = [parse("-y"), parse("x"), parse("0")];
+        assert_eq!(divergence(&field, &xyz), Ok(Expr::Number(0.0)));
+        let zero = vec![Expr::Number(0.0)];
+        assert_eq!(curl(&field, &xyz), Ok(Expr::Matrix(vec![zero.clone(), zero, vec![Expr::Number(2.0)]])));
+        assert!(curl(&field[..2], &xyz[..2]).is_err());
+        assert!(divergence(&field[..2], &xyz).is_err());
+    }
+}
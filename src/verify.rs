@@ -0,0 +1,96 @@
+use crate::complexity::variables;
+use crate::differentiate::differentiate;
+use crate::evaluate::{evaluate, Environment};
+use crate::parser::{BinaryOpKind, Expr};
+use crate::random::Xorshift;
+use crate::render::render_latex;
+use crate::simplify::{collect, simplify};
+
+/// Ergebnis einer erfolgreichen Prüfung einer Stammfunktion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verification {
+    /// `F' - f` vereinfacht sich zu 0
+    Symbolic,
+    /// `F'` und `f` stimmen an der angegebenen Zahl zufälliger Stellen überein
+    Numerical(usize),
+    /// Zu wenige Stellen, an denen beide Seiten definiert sind, z.B. bei `asin(x + 10)`
+    Inconclusive,
+}
+
+// Anzahl der Zufallsstellen und wie viele davon mindestens auswertbar sein müssen
+const SAMPLES: usize = 64;
+const MIN_SAMPLES: usize = 8;
+
+// Relative Toleranz für den numerischen Vergleich
+const TOLERANCE: f64 = 1e-6;
+
+/// Prüft, ob `antiderivative` eine Stammfunktion von `f` nach `var` ist: `F'` wird gebildet, `f`
+/// abgezogen und die Differenz vereinfacht. Bleibt sie nicht 0, werden beide Seiten an zufälligen
+/// Stellen verglichen; freie Parameter erhalten dabei ebenfalls zufällige positive Werte. Gibt einen
+/// Fehler mit einem Gegenbeispiel zurück, wenn `F'` und `f` sich unterscheiden.
+pub fn verify_antiderivative(f: &Expr, antiderivative: &Expr, var: &str) -> Result<Verification, String> {
+    let derivative = simplify(differentiate(antiderivative.clone(), var.to_string()), false);
+    let difference = Expr::BinaryOp(BinaryOpKind::Sub, Box::new(derivative.clone()), Box::new(f.clone()));
+    if collect(simplify(difference.clone(), false)) == Expr::Number(0.0) {
+        return Ok(Verification::Symbolic);
+    }
+    let mut parameters = variables(&difference);
+    parameters.retain(|name| name != var);
+    let mut random = Xorshift::new(0x9e37_79b9_7f4a_7c15);
+    let mut points = 0;
+    for _ in 0..SAMPLES {
+        let mut env = Environment::new();
+        for parameter in &parameters {
+            env.set_variable(parameter.clone(), random.uniform(0.5, 2.0));
+        }
+        let x = random.uniform(-4.0, 4.0);
+        env.set_variable(var.to_string(), x);
+        // Stellen außerhalb des Definitionsbereichs einer der beiden Seiten werden übersprungen
+        let (Ok(expected), Ok(actual)) = (evaluate(f, &env), evaluate(&derivative, &env)) else {
+            continue;
+        };
+        if !expected.is_finite() || !actual.is_finite() {
+            continue;
+        }
+        if (expected - actual).abs() > TOLERANCE * (1.0 + expected.abs().max(actual.abs())) {
+            return Err(format!(
+                "Error: The derivative {} of {} differs from {} at {} = {}",
+                render_latex(&derivative),
+                render_latex(antiderivative),
+                render_latex(f),
+                var,
+                x
+            ));
+        }
+        points += 1;
+    }
+    Ok(if points >= MIN_SAMPLES { Verification::Numerical(points) } else { Verification::Inconclusive })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn verify(f: &str, antiderivative: &str) -> Result<Verification, String> {
+        verify_antiderivative(&parse(f), &parse(antiderivative), "x")
+    }
+
+    #[test]
+    fn test_verify_antiderivative() {
+        assert_eq!(verify("3*x^2 + 2", "x^3 + 2*x + 7"), Ok(Verification::Symbolic));
+        assert!(matches!(verify("sin(x)^2", "x/2 - sin(2*x)/4"), Ok(Verification::Symbolic | Verification::Numerical(_))));
+        assert!(matches!(verify("1/x", "ln(abs(a*x))"), Ok(Verification::Symbolic | Verification::Numerical(_))));
+        assert!(matches!(verify("1/(1 + x^2)", "atan(x)"), Ok(Verification::Symbolic | Verification::Numerical(_))));
+        assert!(verify("2*x", "id(x)^2").is_ok());
+        // Nirgends im Stichprobenbereich definiert
+        assert_eq!(verify("asin(x + 10)", "x*asin(x + 10)"), Ok(Verification::Inconclusive));
+    }
+
+    #[test]
+    fn test_wrong_antiderivative() {
+        assert!(verify("x^2", "x^3").is_err());
+        assert!(verify("cos(x)", "-sin(x)").is_err());
+        assert!(verify("a*x", "x^2/2").is_err());
+    }
+}
@@ -49,17 +49,19 @@ document.querySelector('form#inputForm')?.addEventListener('submit', () => {
             result = wasm.differentiate_expression(input, varInput.value);
             break;
         case 'Integrieren':
-            if (lowerInput.value !== '' || upperInput.value !== '') {
-                result = wasm.integrate_expression(input, varInput.value, parseFloat(lowerInput.value), parseFloat(upperInput.value));
-            } else {
-                result = "";
+            // Fehler wie ein divergentes Integral kommen als Exception aus Rust und werden angezeigt
+            try {
+                result = (lowerInput.value !== '' || upperInput.value !== '')
+                ? wasm.integrate_expression(input, varInput.value, parseFloat(lowerInput.value), parseFloat(upperInput.value))
+                : "";
+            } catch (error) {
+                result = `\\text{${error}}`;
             }
-            result = (lowerInput.value !== '' || upperInput.value !== '')
-            ? wasm.integrate_expression(input, varInput.value, parseFloat(lowerInput.value), parseFloat(upperInput.value))
-            : "";
-            let ad = wasm.find_antiderivative(input, varInput.value);
-            if (ad !== null) {
-                antiderivative = ad;
+            // Ohne geschlossene Form wirft Rust einen Fehler, dann wird keine Stammfunktion angezeigt
+            try {
+                antiderivative = wasm.find_antiderivative(input, varInput.value);
+            } catch {
+                antiderivative = null;
             }
             break;
         case 'Vereinfachen':